@@ -18,4 +18,16 @@ fn main() {
     assert_unique_features!("log", "defmt");
     assert_unique_features!("ariel-os", "external-executor");
     assert_unique_features!("std", "semihosting");
+
+    // The fault handler needs to know whether it's running on a Cortex-M.
+    // The configurable fault status registers (e.g. CFSR) don't exist on ARMv6-M and ARMv8-M Baseline.
+    println!("cargo::rustc-check-cfg=cfg(cortex_m)");
+    println!("cargo::rustc-check-cfg=cfg(armv6m)");
+    let target = std::env::var("TARGET").unwrap_or_default();
+    if target.starts_with("thumbv") {
+        println!("cargo:rustc-cfg=cortex_m");
+    }
+    if target.starts_with("thumbv6m") || target.starts_with("thumbv8m.base") {
+        println!("cargo:rustc-cfg=armv6m");
+    }
 }
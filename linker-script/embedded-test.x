@@ -8,6 +8,13 @@ PROVIDE(embedded_test_linker_file_not_added_to_rustflags = __embedded_test_start
 
 PROVIDE(_embedded_test_setup = __embedded_test_default_setup);
 
+# `#[should_fault]` tests on RISC-V need the exception handlers of the `fault-handler` feature, which are called by riscv-rt.
+# esp-hal handles all exceptions itself (it panics), so the missing symbol below fails the link of such tests.
+PROVIDE(embedded_test_should_fault_needs_riscv_rt = DEFINED(_start_trap_rust_hal) ? embedded_test_should_fault_is_not_supported_with_esp_hal : 0);
+
+# The ESP32-C/H chips don't implement the `mcycle` CSR, so benchmarks read their performance counter with esp-hal.
+PROVIDE(embedded_test_esp_cycle_counter = DEFINED(_start_trap_rust_hal) ? 1 : 0);
+
 # Define a section for the embedded tests and make sure it is not optimized away
 SECTIONS
 {
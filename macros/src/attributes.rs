@@ -1,2 +1,4 @@
 pub(crate) mod setup;
+pub(crate) mod suite;
+pub(crate) mod test;
 pub(crate) mod tests;
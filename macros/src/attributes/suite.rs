@@ -0,0 +1,207 @@
+use crate::attributes::tests::parse::{MacroArgs, Module};
+use crate::attributes::tests::validate::ValidatedModule;
+use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, Group, Punct, Spacing, TokenTree};
+use proc_macro_error3::abort;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, ItemMod, Token, TypeParam, Visibility};
+
+/// `#[embedded_test::suite(F: Bound, <arguments of #[embedded_test::tests]>)]`
+struct SuiteArgs {
+    param: TypeParam,
+    tests_args: proc_macro2::TokenStream,
+}
+
+impl Parse for SuiteArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        if !(input.is_empty() || input.peek(Token![:]) || input.peek(Token![,])) {
+            return Err(syn::Error::new(
+                ident.span(),
+                "expected the type parameter of the suite (e.g. `F` or `F: Trait`) as first argument",
+            ));
+        }
+        let mut param = TypeParam::from(ident);
+        if input.peek(Token![:]) {
+            param.colon_token = Some(input.parse()?);
+            while !input.is_empty() && !input.peek(Token![,]) {
+                param.bounds.push_value(input.parse()?);
+                if input.peek(Token![+]) {
+                    param.bounds.push_punct(input.parse()?);
+                }
+            }
+        }
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        let tests_args = input.parse()?;
+        Ok(SuiteArgs { param, tests_args })
+    }
+}
+
+pub(crate) fn expand(args: TokenStream, item: TokenStream) -> TokenStream {
+    let SuiteArgs { param, tests_args } = match syn::parse(args) {
+        Ok(args) => args,
+        Err(e) => abort!(
+            e.span(),
+            "failed to parse `suite` attribute. Must be of the form #[embedded_test::suite(F)] or #[embedded_test::suite(F: Trait, <arguments of #[embedded_test::tests]>)]. Error: {}",
+            e
+        ),
+    };
+    let macro_args = match MacroArgs::parse(tests_args.clone().into()) {
+        Ok(args) => args,
+        Err(e) => abort!(e),
+    };
+
+    let module = parse_macro_input!(item as ItemMod);
+    let suite_name = module.ident.clone();
+    let is_pub = matches!(module.vis, Visibility::Public(_));
+    let Some((_, items)) = &module.content else {
+        abort!(module, "module must be inline (e.g. `mod foo {}`)",);
+    };
+
+    // Validate the suite once where it is defined, so that errors are not only reported per instantiation
+    ValidatedModule::from_module_and_args(Module::from(module.clone()), macro_args);
+
+    let param_ident = &param.ident;
+    let bound_check = if param.bounds.is_empty() {
+        None
+    } else {
+        let bounds = &param.bounds;
+        Some(quote!(
+            const _: fn() = || {
+                fn __assert_suite_parameter<T: #bounds>() {}
+                __assert_suite_parameter::<#param_ident>();
+            };
+        ))
+    };
+    let attrs = &module.attrs;
+    let body = quote!(
+        #bound_check
+
+        #(#items)*
+    );
+    // `crate::` in the suite refers to the crate which defines the suite, not the one which instantiates it
+    let body = dollar_crate(body);
+    let dollar = Punct::new('$', Spacing::Alone);
+
+    // Suites which are `pub` can be instantiated by other crates
+    let macro_export = is_pub.then(|| quote!(#[macro_export]));
+
+    quote!(
+        #[doc(hidden)]
+        #macro_export
+        macro_rules! #suite_name {
+            (#dollar name:ident, #dollar param:ident) => {
+                #(#attrs)*
+                #[embedded_test::tests(#tests_args)]
+                mod #dollar name {
+                    #[allow(dead_code)]
+                    type #param_ident = super::#dollar param;
+
+                    #body
+                }
+            };
+        }
+    )
+    .into()
+}
+
+/// Replaces `crate::` with `$crate::`
+fn dollar_crate(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let mut result = vec![];
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "crate" => {
+                if matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == ':') {
+                    result.push(TokenTree::Punct(Punct::new('$', Spacing::Alone)));
+                }
+                result.push(TokenTree::Ident(ident));
+            }
+            TokenTree::Group(group) => {
+                let mut new_group = Group::new(group.delimiter(), dollar_crate(group.stream()));
+                new_group.set_span(group.span());
+                result.push(TokenTree::Group(new_group));
+            }
+            token => result.push(token),
+        }
+    }
+    result.into_iter().collect()
+}
+
+/// `instantiate_suite!(path::to::suite, Fixture)` or `instantiate_suite!(path::to::suite, Fixture as name)`
+struct InstantiateArgs {
+    suite: syn::Path,
+    param: syn::Type,
+    name: syn::Ident,
+}
+
+impl Parse for InstantiateArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let suite = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let param: syn::Type = input.parse()?;
+        let name = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            input.parse()?
+        } else {
+            // Derive the name of the instantiation from the parameter, e.g. `W25Q32Fixture` -> `w25q32_fixture`
+            let ident = match &param {
+                syn::Type::Path(path) => path.path.segments.last().map(|s| &s.ident),
+                _ => None,
+            };
+            let Some(ident) = ident else {
+                return Err(syn::Error::new_spanned(
+                    &param,
+                    "cannot derive a name for this instantiation, please provide one (e.g. `Fixture as name`)",
+                ));
+            };
+            format_ident!("{}", snake_case(&ident.to_string()), span = ident.span())
+        };
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        Ok(InstantiateArgs { suite, param, name })
+    }
+}
+
+pub(crate) fn expand_instantiate(input: TokenStream) -> TokenStream {
+    let InstantiateArgs { suite, param, name } = match syn::parse(input) {
+        Ok(args) => args,
+        Err(e) => abort!(
+            e.span(),
+            "failed to parse `instantiate_suite!`. Must be of the form instantiate_suite!(path::to::suite, Type) or instantiate_suite!(path::to::suite, Type as name). Error: {}",
+            e
+        ),
+    };
+
+    // The parameter is aliased next to the instantiation, so that it can be referred to from within the suite module
+    let alias = format_ident!("__{}_SUITE_PARAMETER", name.to_string().to_uppercase());
+    let group = Group::new(Delimiter::Parenthesis, quote!(#name, #alias));
+    quote!(
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        type #alias = #param;
+
+        #suite! #group;
+    )
+    .into()
+}
+
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || (next_is_lower && prev != '_') {
+                result.push('_');
+            }
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
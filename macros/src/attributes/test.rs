@@ -0,0 +1,81 @@
+use crate::attributes::tests::codegen;
+use crate::attributes::tests::parse::{
+    FuncAttribute, FunctionWithAttributes, IgnoreAttribute, TestAttribute, TestMacroArgs,
+};
+use crate::attributes::tests::validate::{TestFunc, ValidatedModule};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use proc_macro_error3::{abort, abort_call_site};
+use syn::{parse_macro_input, ItemFn};
+
+pub(crate) fn expand(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = match TestMacroArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => abort!(e),
+    };
+
+    let mut func = FunctionWithAttributes::from(parse_macro_input!(item as ItemFn));
+    for (attr, span) in &func.attributes {
+        if let FuncAttribute::Init
+        | FuncAttribute::GroupInit
+        | FuncAttribute::Teardown
+        | FuncAttribute::Fixture
+        | FuncAttribute::Test(_)
+        | FuncAttribute::Bench(_) = attr
+        {
+            abort!(
+                span,
+                "`#[init]`, `#[teardown]`, `#[fixture]`, `#[test]` and `#[bench]` can not be used on a freestanding `#[embedded_test::test]` function. Use `#[embedded_test::test(init = path::to::init)]` to initialize the state",
+            );
+        }
+    }
+
+    // The arguments are handled just like the corresponding attributes in a `#[tests]` module
+    let span = Span::call_site();
+    func.attributes
+        .push((FuncAttribute::Test(TestAttribute::default()), span));
+    if let Some(timeout) = args.timeout {
+        func.attributes
+            .push((FuncAttribute::Timeout(timeout), span));
+    }
+    if let Some(ignore) = args.ignore {
+        let reason = ignore.explicit();
+        func.attributes
+            .push((FuncAttribute::Ignore(IgnoreAttribute { reason }), span));
+    }
+    if let Some(should_panic) = args.should_panic {
+        let should_panic = should_panic.unwrap_or_default();
+        func.attributes
+            .push((FuncAttribute::ShouldPanic(should_panic), span));
+    }
+
+    let mut test = TestFunc::from(func);
+    validate(&test, args.init.as_ref());
+    test.init_path = args.init;
+
+    codegen::test(&test, &ValidatedModule::freestanding()).into()
+}
+
+fn validate(test: &TestFunc, init: Option<&syn::Path>) {
+    match (&test.input, init) {
+        (Some(syn::Type::Reference(reference)), _) => {
+            abort!(
+                reference,
+                "parameter must be a single value, not a reference"
+            );
+        }
+        (Some(_), None) => {
+            abort!(
+                test.func.sig,
+                "this test function has an argument but no init function was provided (e.g. `#[embedded_test::test(init = path::to::init)]`)",
+            );
+        }
+        _ => {}
+    }
+
+    if cfg!(feature = "external-executor") && test.asyncness {
+        abort_call_site!(
+            "async freestanding tests are not supported if the feature `external-executor` is enabled, move the test into a `#[embedded_test::tests(executor = ...)]` module instead",
+        );
+    }
+}
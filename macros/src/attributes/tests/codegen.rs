@@ -1,6 +1,9 @@
 mod call_test_fn;
 mod export_sym;
+mod group;
+mod module;
 mod test;
 mod wrap_with_executor;
 
-pub(crate) use test::*;
+pub(crate) use module::*;
+pub(crate) use test::test;
@@ -1,39 +1,331 @@
-use crate::attributes::tests::validate::{InitFunc, TestFunc};
+use crate::attributes::tests::validate::{
+    FixtureFunc, FixtureParam, InitFunc, TeardownFunc, TestCase, TestFunc,
+};
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::ItemFn;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{ItemFn, Type};
 
-fn invoke(func: &ItemFn, args: Vec<TokenStream>) -> TokenStream {
+/// Calls `func`, which is defined `parent_depth` module levels above the test module.
+/// A generic function is instantiated with `type_arg`.
+pub(crate) fn invoke(
+    func: &ItemFn,
+    parent_depth: usize,
+    type_arg: Option<&Type>,
+    args: Vec<TokenStream>,
+) -> TokenStream {
     let ident = &func.sig.ident;
+    let supers = std::iter::repeat_n(quote!(super::), parent_depth);
+    let turbofish = type_arg.map(|ty| quote!(::<#ty>));
     if func.sig.asyncness.is_some() {
-        quote!(#ident(#(#args),*).await)
+        quote!(#(#supers)* #ident #turbofish(#(#args),*).await)
     } else {
-        quote!(#ident(#(#args),*))
+        quote!(#(#supers)* #ident #turbofish(#(#args),*))
     }
 }
 
-/// Generate a code block ( in { ... }) to call the init function (if provided), call the test function and check the outcome.
-pub(crate) fn call_test_fn(test_func: &TestFunc, init_func: Option<&InitFunc>) -> TokenStream {
-    let init_expr = if let Some(init) = init_func {
-        invoke(&init.func, vec![])
+/// The variable holding the value of a fixture
+fn fixture_var(name: &str) -> syn::Ident {
+    format_ident!("__fixture_{}", name)
+}
+
+/// Calls the init function, such that a failing init (returning `Err` or panicking) is reported as such
+fn init_call(call: TokenStream, fallible: bool) -> TokenStream {
+    let call = if fallible {
+        quote!(embedded_test::export::check_init_outcome(#call))
+    } else {
+        call
+    };
+    quote!(
+        {
+            embedded_test::export::enter_init();
+            let state = #call;
+            embedded_test::export::leave_init();
+            state
+        }
+    )
+}
+
+/// Passed to an `info: &TestInfo` parameter
+fn info_arg() -> TokenStream {
+    quote!(embedded_test::export::current_test_info())
+}
+
+/// Passed to a `spawner: Spawner` parameter, it's the spawner of the executor which runs the test
+fn spawner_arg() -> TokenStream {
+    quote!(__spawner)
+}
+
+/// Passed to the `bencher: &mut Bencher` parameter of a `#[bench]` function
+fn bencher_arg() -> TokenStream {
+    quote!(&mut __bencher)
+}
+
+/// Calls an init function, passing the test info and the spawner if it takes them
+pub(crate) fn invoke_init(init: &InitFunc) -> TokenStream {
+    let mut injected = vec![];
+    injected.extend(init.info_param.map(|position| (position, info_arg())));
+    injected.extend(init.spawner_param.map(|position| (position, spawner_arg())));
+    injected.sort_by_key(|(position, _)| *position);
+    let args = injected.into_iter().map(|(_, arg)| arg).collect();
+    init_call(
+        invoke(&init.func, init.parent_depth, None, args),
+        init.fallible,
+    )
+}
+
+/// Passes the fixture to a parameter, either by value or by reference.
+/// A mismatch of the types is reported at the type of the parameter (see `export::FixtureValue`).
+fn fixture_arg(param: &FixtureParam) -> TokenStream {
+    let var = fixture_var(&param.name.to_string());
+    match &param.ty {
+        Type::Reference(r) if r.mutability.is_some() => {
+            quote_spanned!(r.elem.span()=> embedded_test::export::fixture_value_mut(&mut #var))
+        }
+        Type::Reference(r) => {
+            quote_spanned!(r.elem.span()=> embedded_test::export::fixture_value_ref(&#var))
+        }
+        ty => quote_spanned!(ty.span()=> embedded_test::export::fixture_value(#var)),
+    }
+}
+
+/// Passes the state to a parameter of type `ty`, either by value or by reference.
+/// A mismatch of the types is reported at the type of the parameter (see `export::InitState`).
+fn state_arg(ty: &Type) -> TokenStream {
+    let state = quote!(state);
+    match ty {
+        Type::Reference(r) if r.mutability.is_some() => {
+            quote_spanned!(r.elem.span()=> embedded_test::export::init_state_mut(&mut #state))
+        }
+        Type::Reference(r) => {
+            quote_spanned!(r.elem.span()=> embedded_test::export::init_state_ref(&#state))
+        }
+        ty => quote_spanned!(ty.span()=> embedded_test::export::init_state(#state)),
+    }
+}
+
+/// Calls the test function with the `state` (if it takes one), the arguments of the test case
+/// and its fixtures, which are built right before.
+pub(crate) fn test_call(
+    test_func: &TestFunc,
+    case: Option<&TestCase>,
+    fixtures: &[&FixtureFunc],
+) -> TokenStream {
+    let mut test_args: Vec<TokenStream> = test_func.input.iter().map(state_arg).collect();
+    // The arguments of a test case come after the state
+    if let Some(case) = case {
+        test_args.extend(case.args.iter().map(|arg| quote!(#arg)));
+    }
+    // Fixtures, the test info, the spawner and the bencher are passed at the position of their parameter
+    let mut injected: Vec<(usize, TokenStream)> = test_func
+        .fixtures
+        .iter()
+        .map(|(position, param)| (*position, fixture_arg(param)))
+        .collect();
+    injected.extend(test_func.info_param.map(|position| (position, info_arg())));
+    injected.extend(
+        test_func
+            .spawner_param
+            .map(|position| (position, spawner_arg())),
+    );
+    injected.extend(
+        test_func
+            .bencher_param
+            .map(|position| (position, bencher_arg())),
+    );
+    injected.sort_by_key(|(position, _)| *position);
+    for (position, arg) in injected {
+        test_args.insert(position, arg);
+    }
+    let mut run_call = invoke(
+        &test_func.func,
+        0,
+        case.and_then(|c| c.type_arg.as_ref()),
+        test_args,
+    );
+    // The reset is only expected from the test function itself, not from the init function or a fixture
+    if test_func.should_reset.is_some() {
+        run_call = quote!(
+            {
+                embedded_test::export::expect_device_reset();
+                #run_call
+            }
+        );
+    }
+    if !fixtures.is_empty() {
+        let fixture_vars = fixtures.iter().map(|f| fixture_var(&f.name));
+        let fixture_calls = fixtures.iter().map(|f| {
+            let args = f.deps.iter().map(fixture_arg).collect();
+            invoke(&f.func, f.parent_depth, None, args)
+        });
+        return quote!(
+            {
+                #(
+                    #[allow(unused_mut)]
+                    let mut #fixture_vars = #fixture_calls; // fixture(deps..), optionally awaited
+                )*
+                #run_call
+            }
+        );
+    }
+    run_call
+}
+
+/// Generate a code block ( in { ... }) to call the init function (if provided), call the test function,
+/// call the teardown function (if provided) and check the outcome.
+/// The `fixtures` needed by the test are built (in the given order) right before the test function is called.
+/// With `repeat_on_target`, the test function is called repeatedly until it fails (without a reset in between).
+pub(crate) fn call_test_fn(
+    test_func: &TestFunc,
+    case: Option<&TestCase>,
+    init_func: Option<&InitFunc>,
+    teardown_func: Option<&TeardownFunc>,
+    fixtures: &[&FixtureFunc],
+    repeat_on_target: Option<u32>,
+) -> TokenStream {
+    let init_expr = if let Some(init_path) = &test_func.init_path {
+        // The function is not visible to the macro, its return type tells an async or fallible init function apart
+        let check = quote_spanned!(init_path.span()=>
+            {
+                use embedded_test::export::freestanding_init::*;
+                embedded_test::export::check_freestanding_init(
+                    (&&embedded_test::export::FreestandingInit::of(&state)).kind(),
+                );
+            }
+        );
+        let call = init_call(quote!(#init_path()), false);
+        quote!(
+            {
+                let state = #call;
+                #check
+                state
+            }
+        )
+    } else if let Some(init) = init_func {
+        invoke_init(init)
     } else {
         quote!(())
     };
 
-    let run_call = if test_func.input.is_some() {
-        invoke(&test_func.func, vec![quote!(state)])
+    let mut run_call = test_call(test_func, case, fixtures);
+    if let Some(repeat) = repeat_on_target {
+        run_call = quote!(
+            {
+                let mut outcome = #run_call;
+                let mut repetition = 1;
+                while repetition < #repeat && embedded_test::export::outcome_of(&outcome).is_success() {
+                    repetition += 1;
+                    outcome = #run_call;
+                }
+                embedded_test::export::report_repetitions(&outcome, repetition, #repeat);
+                outcome
+            }
+        );
+    }
+
+    // With an expected panic message, the panic handler decides whether the test passed.
+    // Returning from the test is a failure then.
+    // Likewise, the exception handler decides whether a test which should fault passed.
+    // A test which should reset the device fails if it returns, the reset is reported by the next boot.
+    // A stage of a multi-stage test, which is not the last one, asks the runner to reset the device instead of passing.
+    // A benchmark reports the cycles measured by its bencher.
+    let (before_init, check_outcome) = if let Some(expected) = &test_func.expected_panic {
+        // Fails to compile without a panic handler which checks the message, reported at the test
+        let check_supported = quote_spanned!(test_func.func.sig.ident.span()=>
+            embedded_test::export::check_expected_panic_supported!();
+        );
+        (
+            quote!(
+                #check_supported
+                embedded_test::export::hosting::expect_panic(#expected);
+            ),
+            quote!(embedded_test::export::check_outcome_expecting_panic(outcome, #expected)),
+        )
+    } else if let Some(kind) = &test_func.should_fault {
+        let kind = match kind {
+            Some(kind) => quote!(Some(#kind)),
+            None => quote!(None),
+        };
+        (
+            quote!(embedded_test::export::expect_fault(#kind);),
+            quote!(embedded_test::export::check_outcome_expecting_fault(
+                outcome
+            )),
+        )
+    } else if test_func.should_reset.is_some() {
+        (
+            quote!(),
+            quote!(embedded_test::export::check_outcome_expecting_reset(
+                outcome
+            )),
+        )
+    } else if let Some(stages) = test_func.stages {
+        (
+            quote!(embedded_test::export::check_stage(#stages);),
+            quote!(embedded_test::export::check_stage_outcome(outcome, #stages)),
+        )
+    } else if let Some(bench) = &test_func.bench {
+        let (warmup, iterations) = (bench.warmup, bench.iterations);
+        // Fails to compile on targets without a cycle counter, reported at the benchmark
+        let check_supported = quote_spanned!(test_func.func.sig.ident.span()=>
+            embedded_test::export::check_bench_supported!();
+        );
+        (
+            quote!(
+                #check_supported
+                let mut __bencher = embedded_test::export::bencher(#warmup, #iterations);
+            ),
+            quote!(embedded_test::export::check_bench_outcome(
+                outcome,
+                &mut __bencher
+            )),
+        )
     } else {
-        invoke(&test_func.func, vec![])
+        (
+            quote!(),
+            quote!(embedded_test::export::check_outcome(outcome)),
+        )
     };
 
+    let Some(teardown) = teardown_func else {
+        return quote!(
+            {
+                #before_init
+                let outcome;
+                {
+                    let state = #init_expr; // either init() or init().await or ()
+                    outcome = #run_call; // either test(state, ..), test(state, ..).await, test(..), or test(..).await
+                }
+                #check_outcome;
+            }
+        );
+    };
+
+    let mut teardown_args = vec![];
+    if let Some(state) = &teardown.state {
+        teardown_args.push(state_arg(state));
+    }
+    if teardown.takes_outcome() {
+        teardown_args.push(quote!(embedded_test::export::outcome_of(&outcome)));
+    }
+    if let Some(position) = teardown.info_param {
+        teardown_args.insert(position, info_arg());
+    }
+    let teardown_call = invoke(&teardown.func, teardown.parent_depth, None, teardown_args);
+
     quote!(
         {
+            #before_init
             let outcome;
+            let teardown_outcome;
             {
-                let state = #init_expr; // either init() or init().await or ()
-                outcome = #run_call; // either test(state), test(state).await, test(), or test().await
+                #[allow(unused_mut)]
+                let mut state = #init_expr; // either init() or init().await or ()
+                outcome = #run_call; // test(state), test(&state), test(&mut state) or test(), optionally awaited
+                teardown_outcome = #teardown_call; // teardown(state, outcome), teardown(outcome) or teardown(), optionally awaited
             }
-            embedded_test::export::check_outcome(outcome);
+            embedded_test::export::check_teardown_outcome(teardown_outcome);
+            #check_outcome;
         }
     )
 }
@@ -1,54 +1,197 @@
-use crate::attributes::tests::validate::TestFunc;
+use crate::attributes::tests::validate::{TestFunc, ValidatedModule};
 use proc_macro::Span;
 use proc_macro2::Ident;
 use quote::{format_ident, quote};
+use std::fmt::Write as _;
 use std::hash::{DefaultHasher, Hash as _, Hasher as _};
 
 pub(crate) fn export_sym(
     test: &TestFunc,
+    ident: &Ident,
+    test_name: &str,
     ident_entrypoint: Ident,
-    default_timeout: Option<u32>,
+    module: &ValidatedModule,
+    repeat_on_target: bool,
+    group: Option<(&str, usize)>,
 ) -> proc_macro2::TokenStream {
+    let macro_args = &module.macro_args;
     let cfgs = &test.cfgs;
-    let should_panic = test.should_panic;
+    // If an expected panic message is given, the target checks the message itself and exits successfully on a match
+    let should_panic = test.should_panic && test.expected_panic.is_none();
     let ignore = test.ignore;
-    let test_name = &test.func.sig.ident;
-    let ident_var = format_ident!("__{}_SYM", test_name.to_string().to_uppercase());
-    let timeout = test.timeout.or(default_timeout);
+    let ignore_reason = test.ignore_reason.as_deref();
+    let ident_var = format_ident!("__embedded_test_sym_{}", ident);
+    let timeout = test.timeout.or(macro_args.default_timeout);
+    // The tests of a group are run once, the defaults of the module don't apply to them
+    let retry = test
+        .retry
+        .or(macro_args.default_retry)
+        .filter(|_| group.is_none());
+    // A benchmark is not repeated, not even with the `default_repeat` of the module
+    let repeat = test
+        .repeat
+        .or(macro_args.default_repeat)
+        .filter(|_| test.bench.is_none() && group.is_none());
+    let bench = test.bench.is_some();
+    let stages = test.stages;
+    let should_reset = test.should_reset.is_some();
+    let should_reset_within = test.should_reset.flatten().map(|within| within.millis);
+
+    let tags = tags(test, module);
+    let meta = &test.meta;
 
     if cfg!(feature = "std") {
         // Export test as struct so that we can collect it using linkme when on std
-        let timeout = if let Some(timeout) = timeout {
-            quote!(Some(#timeout))
+        let (timeout, timeout_ms) = if let Some(timeout) = timeout {
+            let (secs, millis) = (timeout.secs(), timeout.millis);
+            (quote!(Some(#secs)), quote!(Some(#millis)))
+        } else {
+            (quote!(None), quote!(None))
+        };
+        let ignore_reason = if let Some(reason) = ignore_reason {
+            quote!(Some(#reason))
+        } else {
+            quote!(None)
+        };
+        let retry = if let Some(retry) = retry {
+            quote!(Some(#retry))
         } else {
             quote!(None)
         };
+        let repeat = if let Some(repeat) = repeat {
+            quote!(Some(#repeat))
+        } else {
+            quote!(None)
+        };
+        let should_reset_within_ms = if let Some(millis) = should_reset_within {
+            quote!(Some(#millis))
+        } else {
+            quote!(None)
+        };
+        let stages = if let Some(stages) = stages {
+            quote!(Some(#stages))
+        } else {
+            quote!(None)
+        };
+        let kind = if bench {
+            quote!(Some("bench"))
+        } else {
+            quote!(None)
+        };
+        let meta = meta.iter().map(|(key, value)| quote!((#key, #value)));
+        let (group, group_index) = if let Some((group, index)) = group {
+            (quote!(Some(#group)), quote!(Some(#index)))
+        } else {
+            (quote!(None), quote!(None))
+        };
         quote!(
             #(#cfgs)*
             #[embedded_test::export::hosting::distributed_slice(embedded_test::export::hosting::TESTS)]
             #[linkme(crate= embedded_test::export::hosting::linkme)]
+            #[allow(non_upper_case_globals)]
                 static #ident_var: embedded_test::export::hosting::Test = embedded_test::export::hosting::Test {
-                    name:  concat!(module_path!(), "::", stringify!(#test_name)),
+                    name:  concat!(module_path!(), "::", #test_name),
                     function: #ident_entrypoint,
                     should_panic: #should_panic,
+                    should_reset: #should_reset,
+                    should_reset_within_ms: #should_reset_within_ms,
                     ignored: #ignore,
+                    ignore_reason: #ignore_reason,
                     timeout: #timeout,
+                    timeout_ms: #timeout_ms,
+                    retry: #retry,
+                    repeat: #repeat,
+                    repeat_on_target: #repeat_on_target,
+                    stages: #stages,
+                    kind: #kind,
+                    group: #group,
+                    group_index: #group_index,
+                    tags: &[#(#tags),*],
+                    meta: &[#(#meta),*],
             };
         )
     } else {
         // Generate a symbol name which is actually a JSON object describing the test so that probe-rs can parse it.
 
+        // Fields which are only present if set
+        let mut optional_fields = String::new();
+        if let Some(timeout) = timeout {
+            // `timeout` (in seconds) is kept for runners which don't know `timeout_ms` yet
+            write!(
+                optional_fields,
+                ",\"timeout\":{},\"timeout_ms\":{}",
+                timeout.secs(),
+                timeout.millis
+            )
+            .unwrap();
+        }
+        if let Some(retry) = retry {
+            write!(optional_fields, ",\"retry\":{retry}").unwrap();
+        }
+        if let Some(repeat) = repeat {
+            write!(optional_fields, ",\"repeat\":{repeat}").unwrap();
+            if repeat_on_target {
+                // The repetitions are done by the test itself, the runner must only run it once
+                optional_fields.push_str(",\"repeat_on_target\":true");
+            }
+        }
+        if should_reset {
+            // The runner starts the test again after the reset, the target then reports that the reset was observed
+            optional_fields.push_str(",\"should_reset\":true");
+            if let Some(millis) = should_reset_within {
+                write!(optional_fields, ",\"should_reset_within_ms\":{millis}").unwrap();
+            }
+        }
+        if let Some(stages) = stages {
+            // The runner resets the device between the stages, and reports them as a single test
+            write!(optional_fields, ",\"stages\":{stages}").unwrap();
+        }
+        if bench {
+            // The runner only runs benchmarks when asked to, and collects the reported statistics instead of a pass/fail
+            optional_fields.push_str(",\"kind\":\"bench\"");
+        }
+        if let Some((group, index)) = group {
+            // The runner must not reset the device between the tests of a group
+            write!(
+                optional_fields,
+                ",\"group\":\"{}\",\"group_index\":{}",
+                _json_escape(group),
+                index
+            )
+            .unwrap();
+        }
+        if let Some(reason) = ignore_reason {
+            write!(
+                optional_fields,
+                ",\"ignore_reason\":\"{}\"",
+                _json_escape(reason)
+            )
+            .unwrap();
+        }
+        if !tags.is_empty() {
+            let tags: Vec<String> = tags
+                .iter()
+                .map(|tag| format!("\"{}\"", _json_escape(tag)))
+                .collect();
+            write!(optional_fields, ",\"tags\":[{}]", tags.join(",")).unwrap();
+        }
+        if !meta.is_empty() {
+            let meta: Vec<String> = meta
+                .iter()
+                .map(|(key, value)| {
+                    format!("\"{}\":\"{}\"", _json_escape(key), _json_escape(value))
+                })
+                .collect();
+            write!(optional_fields, ",\"meta\":{{{}}}", meta.join(",")).unwrap();
+        }
+
         let sym_name = format!(
             r#"{{"disambiguator":{},"name":"{}","ignored":{},"should_panic":{}{}}}"#,
-            _crate_local_disambiguator(), // disambiguator is needed to allow multiple identical test in different modules
-            _json_escape(&test_name.to_string()),
+            _module_disambiguator(&module.path), // disambiguator is needed to allow multiple identical test in different modules
+            _json_escape(test_name),
             if ignore { "true" } else { "false" },
             if should_panic { "true" } else { "false" },
-            if let Some(timeout) = timeout {
-                format!(",\"timeout\":{timeout}")
-            } else {
-                String::new()
-            }
+            optional_fields
         );
 
         // Unfortunately the module path can not be extracted from the Span yet.
@@ -60,11 +203,23 @@ pub(crate) fn export_sym(
             //#[no_mangle]
             #[link_section = ".embedded_test.tests"]
             #[export_name = #sym_name]
+            #[allow(non_upper_case_globals)]
             static #ident_var: (fn()->!,&'static str) = (#ident_entrypoint, module_path!());
         )
     }
 }
 
+/// The tags of the test. Tags of the module are inherited by all tests.
+pub(crate) fn tags(test: &TestFunc, module: &ValidatedModule) -> Vec<String> {
+    let mut tags: Vec<String> = module.macro_args.tags.iter().map(|t| t.value()).collect();
+    for tag in &test.tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
+
 fn _hash(string: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
     string.hash(&mut hasher);
@@ -78,8 +233,16 @@ pub(crate) fn _crate_local_disambiguator() -> u64 {
     _hash(&format!("{:?}", Span::call_site()))
 }
 
+/// Disambiguator for tests in (possibly nested) modules of a single `#[tests]` invocation
+fn _module_disambiguator(path: &[String]) -> u64 {
+    if path.is_empty() {
+        _crate_local_disambiguator()
+    } else {
+        _hash(&format!("{:?}::{}", Span::call_site(), path.join("::")))
+    }
+}
+
 fn _json_escape(string: &str) -> String {
-    use std::fmt::Write;
     let mut escaped = String::new();
     for c in string.chars() {
         match c {
@@ -0,0 +1,133 @@
+use crate::attributes::tests::codegen::call_test_fn::{invoke_init, test_call};
+use crate::attributes::tests::codegen::test::{entry_name, info_ident};
+use crate::attributes::tests::codegen::wrap_with_executor::wrap_with_executor;
+use crate::attributes::tests::validate::{InitFunc, TestCase, TestFunc, ValidatedModule};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse_quote;
+
+/// Identifier of the function which runs the tests of a `#[group_init]` group
+fn runner_ident(group_init: &InitFunc) -> syn::Ident {
+    format_ident!("__{}_group", group_init.name)
+}
+
+/// Position of a test (case) within the group, which is the order the tests are run in
+pub(crate) fn group_index(module: &ValidatedModule, test: &TestFunc, case: Option<usize>) -> usize {
+    let preceding: usize = module
+        .tests
+        .iter()
+        .take_while(|t| t.func.sig.ident != test.func.sig.ident)
+        .map(|t| t.cases.len().max(1))
+        .sum();
+    preceding + case.unwrap_or_default()
+}
+
+/// The entrypoint of a test of the group starts the group runner at the position of the test
+pub(crate) fn enter_group(group_init: &InitFunc, index: usize) -> TokenStream {
+    let runner = runner_ident(group_init);
+    quote!(
+        {
+            embedded_test::export::enter_group(#index);
+            #runner()
+        }
+    )
+}
+
+/// Generates the group runner: It calls the `#[group_init]` function once and runs the tests of the module
+/// one after another, starting at the test the runner requested.
+/// With the `run_group` command all following tests are run as well, otherwise only the requested test.
+pub(crate) fn group(module: &ValidatedModule, group_init: &InitFunc) -> TokenStream {
+    let mut tests = vec![];
+    let mut asyncness = group_init.asyncness;
+    for test in &module.tests {
+        let fixtures = module.fixtures_for_test(test);
+        asyncness |= test.asyncness || fixtures.iter().any(|f| f.asyncness);
+
+        // An ignored test is skipped by the group runner, just like a test whose `#[ignore_unless]` predicate doesn't hold
+        let mut cfgs = test.cfgs.clone();
+        let mut skip = None;
+        if test.ignore {
+            skip = Some((
+                test.cfgs.clone(),
+                test.ignore_reason.clone().unwrap_or_default(),
+            ));
+        } else if let Some(predicate) = &test.ignore_unless {
+            cfgs.push(parse_quote!(#[cfg(#predicate)]));
+            let mut skip_cfgs = test.cfgs.clone();
+            skip_cfgs.push(parse_quote!(#[cfg(not(#predicate))]));
+            skip = Some((
+                skip_cfgs,
+                format!("ignored unless `{}`", quote!(#predicate)),
+            ));
+        }
+        let cases: Vec<Option<(usize, &TestCase)>> = if test.cases.is_empty() {
+            vec![None]
+        } else {
+            test.cases.iter().enumerate().map(Some).collect()
+        };
+        for case in cases {
+            let (ident, name) = entry_name(test, case);
+            let info = info_ident(&ident);
+            let index = group_index(module, test, case.map(|(i, _)| i));
+            if let Some((skip_cfgs, reason)) = &skip {
+                tests.push(quote!(
+                    #(#skip_cfgs)*
+                    {
+                        if embedded_test::export::group_runs(#index) {
+                            embedded_test::export::skip_group_test(#name, #reason);
+                        }
+                    }
+                ));
+                if test.ignore {
+                    continue;
+                }
+            }
+            let run_call = test_call(test, case.map(|(_, c)| c), &fixtures);
+            tests.push(quote!(
+                #(#cfgs)*
+                {
+                    if embedded_test::export::group_runs(#index) {
+                        embedded_test::export::enter_test(&#info);
+                        let outcome = #run_call; // test(&state), test(&mut state) or test(), optionally awaited
+                        embedded_test::export::check_group_outcome(outcome, #name);
+                    }
+                }
+            ));
+        }
+    }
+
+    let init_call = invoke_init(group_init);
+    let mut block = quote!(
+        {
+            #[allow(unused_mut, unused_variables)]
+            let mut state = #init_call; // either group_init() or group_init().await
+
+            #(#tests)*
+
+            embedded_test::export::hosting::exit(0)
+        }
+    );
+
+    let runner = runner_ident(group_init);
+    let mut embassy_task = None;
+    if asyncness {
+        let additional_output;
+        (block, additional_output) = wrap_with_executor(
+            &format_ident!("{}_group", group_init.name),
+            &[],
+            module.macro_args.executor.as_ref(),
+            block,
+        );
+        embassy_task = Some(additional_output);
+    }
+
+    quote!(
+        #embassy_task
+
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        fn #runner() -> ! {
+            #block
+        }
+    )
+}
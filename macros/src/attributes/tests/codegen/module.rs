@@ -0,0 +1,123 @@
+use crate::attributes::tests::codegen::group::group;
+use crate::attributes::tests::codegen::test::test;
+use crate::attributes::tests::parse::attribute_names;
+use crate::attributes::tests::validate::ValidatedModule;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, Item, Meta, Token};
+
+/// Generates the (possibly nested) test module with all its tests and nested modules
+pub(crate) fn module(module: &ValidatedModule) -> TokenStream {
+    let untouched_tokens = &module.untouched_tokens;
+    let tests = module.tests.iter().map(|t| test(t, module));
+    // Inherited init/teardown/fixture functions are already defined in one of the parent modules
+    let init_fns = module
+        .init_funcs
+        .values()
+        .filter(|i| i.parent_depth == 0)
+        .map(|i| &i.func);
+    let teardown_fn = module
+        .teardown
+        .as_ref()
+        .filter(|t| t.parent_depth == 0)
+        .map(|t| &t.func);
+    let fixture_fns = module
+        .fixtures
+        .iter()
+        .filter(|f| f.parent_depth == 0)
+        .map(|f| &f.func);
+    let group_init = module.group_init.as_ref();
+    let group_init_fn = group_init.map(|g| &g.func);
+    let group_runner = group_init.map(|g| group(module, g));
+    let submodules = module.submodules.iter().map(self::module);
+    let warnings = uncollected_tests(module);
+
+    let attrs = &module.attrs;
+    let vis = &module.vis;
+    let mod_name = format_ident!("{}", module.module_name);
+    quote!(
+        #(#attrs)*
+        #vis mod #mod_name {
+            #(#untouched_tokens)*
+
+            #(#init_fns)*
+
+            #group_init_fn
+
+            #teardown_fn
+
+            #(#fixture_fns)*
+
+            #group_runner
+
+            #(#tests)*
+
+            #(#submodules)*
+
+            #(#warnings)*
+        }
+    )
+}
+
+/// Warns about functions in the module which look like tests, but are not collected (and would never run)
+fn uncollected_tests(module: &ValidatedModule) -> Vec<TokenStream> {
+    let functions = module
+        .untouched_tokens
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fn(func) => Some(func),
+            _ => None,
+        })
+        .chain(module.tests.iter().map(|test| &test.func));
+    let mut warnings = vec![];
+    for attr in functions.flat_map(|func| &func.attrs) {
+        let path = attr.path();
+        // e.g. `#[tokio::test]`, which would be a libtest test (`#[embedded_test::test]` is collected on its own)
+        if path.segments.len() > 1
+            && path.segments.last().is_some_and(|s| s.ident == "test")
+            && path
+                .segments
+                .first()
+                .is_some_and(|s| s.ident != "embedded_test")
+        {
+            warnings.push(warning(
+                path.span(),
+                "this test is not collected by embedded-test and will never run, use `#[test]` instead",
+            ));
+        }
+        // `cfg_attr` is only evaluated after the test module has been generated
+        if path.is_ident("cfg_attr") && cfg_attr_contains_attribute(attr) {
+            warnings.push(warning(
+                path.span(),
+                "embedded-test attributes in `#[cfg_attr(...)]` are not recognized (a `#[test]` in there is never run), use `#[cfg(...)]` or `#[ignore_unless(...)]` instead",
+            ));
+        }
+    }
+    warnings
+}
+
+fn cfg_attr_contains_attribute(attr: &Attribute) -> bool {
+    let Ok(metas) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+        return false;
+    };
+    metas.iter().skip(1).any(|meta| {
+        meta.path()
+            .get_ident()
+            .is_some_and(|ident| attribute_names().any(|name| ident == name))
+    })
+}
+
+/// Emits a warning (there is no stable API for warnings from proc-macros, so a deprecated constant is used)
+fn warning(span: Span, message: &str) -> TokenStream {
+    let ident = syn::Ident::new("embedded_test_warning", span);
+    quote_spanned!(span=>
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_upper_case_globals)]
+            const #ident: () = ();
+            #ident
+        };
+    )
+}
@@ -1,49 +1,165 @@
 use crate::attributes::tests::codegen::call_test_fn::call_test_fn;
-use crate::attributes::tests::codegen::export_sym::export_sym;
+use crate::attributes::tests::codegen::export_sym::{export_sym, tags};
+use crate::attributes::tests::codegen::group::{enter_group, group_index};
 use crate::attributes::tests::codegen::wrap_with_executor::wrap_with_executor;
-use crate::attributes::tests::validate::{TestFunc, ValidatedModule};
+use crate::attributes::tests::validate::{TestCase, TestFunc, ValidatedModule};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
+use syn::{parse_quote, Ident};
 
 pub(crate) fn test(test: &TestFunc, module: &ValidatedModule) -> TokenStream {
+    let test_func = &test.func;
+
+    // A parameterized test gets one entrypoint per test case
+    let entries = if test.cases.is_empty() {
+        vec![test_entry(test, None, module)]
+    } else {
+        test.cases
+            .iter()
+            .enumerate()
+            .map(|(i, case)| test_entry(test, Some((i, case)), module))
+            .collect()
+    };
+
+    // With `#[ignore_unless(...)]` the test function only exists if the predicate holds
+    let predicate = test
+        .ignore_unless
+        .as_ref()
+        .map(|predicate| quote!(#[cfg(#predicate)]));
+
+    // The group runner never calls an ignored test
+    let allow_unused =
+        (test.ignore && module.group_init.is_some()).then(|| quote!(#[allow(dead_code)]));
+
+    quote! {
+        #predicate
+        #allow_unused
+        #[cfg_attr(rust_analyzer, ::core::prelude::v1::test)]
+        #test_func
+
+        #(#entries)*
+    }
+}
+
+/// Returns the identifier (used to derive the names of generated items) and the name of a test entry.
+/// The identifier of a case is reserved, so that it cannot collide with a test named e.g. `foo_case_1`.
+pub(crate) fn entry_name(test: &TestFunc, case: Option<(usize, &TestCase)>) -> (Ident, String) {
     let ident = &test.func.sig.ident;
-    let ident_entrypoint = format_ident!("__{}_entrypoint", ident);
+    match case {
+        None => (ident.clone(), ident.to_string()),
+        Some((i, case)) => (
+            format_ident!("__embedded_test_{}_case_{}", ident, i + 1),
+            format!("{}::{}", ident, case.name),
+        ),
+    }
+}
+
+/// Identifier of the `TestInfo` static of a test entry
+/// Derived from the unmodified identifier, uppercasing it could make two tests collide (e.g. `ab` and `AB`)
+pub(crate) fn info_ident(ident: &Ident) -> Ident {
+    format_ident!("__embedded_test_info_{}", ident)
+}
+
+/// Generates the `TestInfo` static, which is available at runtime via `embedded_test::current_test()`
+fn test_info(
+    test: &TestFunc,
+    ident: &Ident,
+    test_name: &str,
+    module: &ValidatedModule,
+) -> TokenStream {
+    let info = info_ident(ident);
     let cfgs = &test.cfgs;
-    let test_func = &test.func;
-    let mut embassy_task = None;
+    let timeout = match test.timeout.or(module.macro_args.default_timeout) {
+        Some(timeout) => {
+            let millis = u64::from(timeout.millis);
+            quote!(Some(::core::time::Duration::from_millis(#millis)))
+        }
+        None => quote!(None),
+    };
+    let tags = tags(test, module);
+    let should_panic = test.should_panic;
+    quote!(
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        #(#cfgs)*
+        static #info: embedded_test::TestInfo = embedded_test::export::test_info(
+            #test_name,
+            module_path!(),
+            #timeout,
+            &[#(#tags),*],
+            #should_panic,
+        );
+    )
+}
 
-    // Generate the code block that will call init, run the test and check the outcome.
-    let init = module.init_function_for_test(test);
-    let mut test_invocation = call_test_fn(test, init);
+/// Generates the entrypoint and the exported symbol for a test or for a single case of a parameterized test.
+fn test_entry(
+    test: &TestFunc,
+    case: Option<(usize, &TestCase)>,
+    module: &ValidatedModule,
+) -> TokenStream {
+    if let Some(predicate) = &test.ignore_unless {
+        // The test is only compiled if the predicate holds. Otherwise, a placeholder is exported,
+        // so that the test is still listed (as ignored) by the runner.
+        let mut enabled = test.clone();
+        enabled.ignore_unless = None;
+        enabled.cfgs.push(parse_quote!(#[cfg(#predicate)]));
 
-    let init_is_async = init.map(|i| i.asyncness).unwrap_or_default();
+        let mut disabled = test.clone();
+        disabled.ignore = true;
+        disabled.ignore_reason = Some(format!("ignored unless `{}`", quote!(#predicate)));
+        disabled.cfgs.push(parse_quote!(#[cfg(not(#predicate))]));
 
-    // If the test is async or the init function is async, we need to wrap the test invocation in an executor.
-    // Result is still a block
-    if test.asyncness || init_is_async {
-        let additional_output;
-        (test_invocation, additional_output) =
-            wrap_with_executor(test, module.macro_args.executor.as_ref(), test_invocation);
-        embassy_task = Some(additional_output);
+        let enabled_entry = test_entry(&enabled, case, module);
+        let placeholder_entry = ignored_placeholder_entry(&disabled, case, module);
+        return quote!(
+            #enabled_entry
+
+            #placeholder_entry
+        );
     }
 
+    let (ident, test_name) = entry_name(test, case);
+    let ident_entrypoint = format_ident!("__{}_entrypoint", ident);
+    let cfgs = &test.cfgs;
+
+    // The tests of a `#[group_init]` module are run by the group runner, which calls the group init only once
+    let group = module
+        .group_init
+        .as_ref()
+        .map(|group_init| (group_init, group_index(module, test, case.map(|(i, _)| i))));
+    let (test_invocation, embassy_task, repeat_on_target) = match group {
+        Some((group_init, index)) => (enter_group(group_init, index), None, None),
+        None => test_invocation(test, case, &ident, module),
+    };
+
     // Now generate an entrypoint function that will be called by the test runner.
     // This function has the signature () -> !, so it will never return.
     // Instead, it will signal the test result via semihosting exit/abort instead
+    let info = info_ident(&ident);
+    let test_info = test_info(test, &ident, &test_name, module);
     let test_entrypoint = quote!(
         #[doc(hidden)]
         #(#cfgs)*
         fn #ident_entrypoint() -> ! {
+           embedded_test::export::enter_test(&#info);
            #test_invocation
         }
     );
 
     // A static symbol that will be exported that describes the test and can be parsed by probe-rs.
-    let sym = export_sym(test, ident_entrypoint, module.macro_args.default_timeout);
+    let sym = export_sym(
+        test,
+        &ident,
+        &test_name,
+        ident_entrypoint,
+        module,
+        repeat_on_target.is_some(),
+        group.map(|(group_init, index)| (group_init.name.as_str(), index)),
+    );
 
     quote! {
-        #[cfg_attr(rust_analyzer, ::core::prelude::v1::test)]
-        #test_func
+        #test_info
 
         #embassy_task
 
@@ -53,3 +169,94 @@ pub(crate) fn test(test: &TestFunc, module: &ValidatedModule) -> TokenStream {
 
     }
 }
+
+/// Generates the code block that will call init, run the test and check the outcome
+/// (wrapped in an executor if needed, which is returned as well), and the number of repetitions on the target.
+fn test_invocation(
+    test: &TestFunc,
+    case: Option<(usize, &TestCase)>,
+    ident: &Ident,
+    module: &ValidatedModule,
+) -> (TokenStream, Option<TokenStream>, Option<u32>) {
+    let init = module.init_function_for_test(test);
+    let teardown = module.teardown.as_ref();
+    let fixtures = module.fixtures_for_test(test);
+
+    // Repetitions are done on the target (saving a reset per repetition), unless there is an init state
+    // which is consumed by the test or fixtures which would have to be built again (e.g. taking peripherals).
+    // Tests which should panic, fault or reset can't be repeated on the target either, and neither can multi-stage tests.
+    // Benchmarks run their closure repeatedly anyway.
+    let repeat = test
+        .repeat
+        .or(module.macro_args.default_repeat)
+        .unwrap_or(1);
+    let repeat_on_target = (repeat > 1
+        && !test.should_panic
+        && test.should_reset.is_none()
+        && test.should_fault.is_none()
+        && test.stages.is_none()
+        && test.bench.is_none()
+        && test.init_path.is_none()
+        && fixtures.is_empty()
+        && init.is_none_or(|init| init.state.is_none()))
+    .then_some(repeat);
+
+    let mut embassy_task = None;
+    let mut test_invocation = call_test_fn(
+        test,
+        case.map(|(_, c)| c),
+        init,
+        teardown,
+        &fixtures,
+        repeat_on_target,
+    );
+
+    // If the test, the init function, the teardown function or a fixture is async, we need to wrap the test invocation in an executor.
+    // A test with its own executor is always run by it. Result is still a block
+    if module.test_is_async(test) || test.executor.is_some() {
+        let additional_output;
+        (test_invocation, additional_output) = wrap_with_executor(
+            ident,
+            &test.cfgs,
+            test.executor
+                .as_ref()
+                .or(module.macro_args.executor.as_ref()),
+            test_invocation,
+        );
+        embassy_task = Some(additional_output);
+    }
+
+    (test_invocation, embassy_task, repeat_on_target)
+}
+
+/// Generates an entrypoint which skips the test, and the exported symbol which lists the test as ignored.
+fn ignored_placeholder_entry(
+    test: &TestFunc,
+    case: Option<(usize, &TestCase)>,
+    module: &ValidatedModule,
+) -> TokenStream {
+    let (ident, test_name) = entry_name(test, case);
+    let ident_entrypoint = format_ident!("__{}_entrypoint", ident);
+    let cfgs = &test.cfgs;
+    let reason = test.ignore_reason.as_deref().unwrap_or_default();
+
+    let sym = export_sym(
+        test,
+        &ident,
+        &test_name,
+        ident_entrypoint.clone(),
+        module,
+        false,
+        None,
+    );
+
+    quote! {
+        #[doc(hidden)]
+        #(#cfgs)*
+        fn #ident_entrypoint() -> ! {
+            embedded_test::export::skip(#reason)
+        }
+
+        #sym
+    }
+}
@@ -1,19 +1,22 @@
-use crate::attributes::tests::validate::TestFunc;
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
-use syn::Expr;
+use syn::{Attribute, Expr};
 
 /// wraps the provided block in an embassy task, optionally using a custom executor.
 /// Returns a block that spawns the task and also returns the task function itself.
 pub(crate) fn wrap_with_executor(
-    test: &TestFunc,
+    ident: &Ident,
+    cfgs: &[Attribute],
     custom_executor: Option<&Expr>,
     block: TokenStream,
 ) -> (TokenStream, TokenStream) {
-    let task_attribute = if cfg!(feature = "ariel-os") {
-        quote!(ariel_os::task)
+    let (task_attribute, spawner_type) = if cfg!(feature = "ariel-os") {
+        (quote!(ariel_os::task), quote!(ariel_os::asynch::Spawner))
     } else {
-        quote!(embedded_test::export::task)
+        (
+            quote!(embedded_test::export::task),
+            quote!(embedded_test::export::Spawner),
+        )
     };
 
     let executor_constructor = if let Some(executor) = custom_executor {
@@ -26,30 +29,32 @@ pub(crate) fn wrap_with_executor(
         }
     };
 
-    let cfgs = &test.cfgs;
-    let ident_invoker = format_ident!("__{}_invoker", test.func.sig.ident);
+    let ident_invoker = format_ident!("__{}_invoker", ident);
 
-    // We need to create a new function annotated with the task attribute, to spawn an async task
+    // We need to create a new function annotated with the task attribute, to spawn an async task.
+    // The spawner is passed on to the init and test functions which take a `Spawner` parameter.
     let task_fn = quote!(
           #(#cfgs)*
           #[#task_attribute]
           #[doc(hidden)]
-          async fn #ident_invoker() {
+          #[allow(unused_variables)]
+          async fn #ident_invoker(__spawner: #spawner_type) {
               #block
           }
     );
 
     let spawn_invoker = if cfg!(feature = "embassy-09") {
-        quote!( spawn(#ident_invoker()).unwrap() )
+        quote!( spawn(#ident_invoker(spawner)).unwrap() )
     } else if cfg!(feature = "embassy-010") {
-        quote!( spawn(#ident_invoker().unwrap()) )
+        quote!( spawn(#ident_invoker(spawner).unwrap()) )
     } else {
         panic!("must select embassy-09 or embassy-10 feature");
     };
 
     let spawner_block = if cfg!(feature = "ariel-os") {
         quote!( {
-            ariel_os::asynch::spawner().#spawn_invoker;
+            let spawner = ariel_os::asynch::spawner();
+            spawner.#spawn_invoker;
             ariel_os::thread::park();
             unreachable!();
         })
@@ -4,3 +4,5 @@ mod macro_args;
 pub(crate) use macro_args::*;
 mod module;
 pub(crate) use module::*;
+mod timeout;
+pub(crate) use timeout::*;
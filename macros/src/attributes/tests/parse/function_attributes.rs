@@ -1,3 +1,4 @@
+use crate::attributes::tests::parse::Timeout;
 use darling::FromMeta;
 use proc_macro_error3::abort;
 use syn::spanned::Spanned;
@@ -6,50 +7,324 @@ use syn::{Attribute, ItemFn};
 /// Represents the attributes that can be applied to a function in the test module
 pub(crate) enum FuncAttribute {
     Init,
+    GroupInit,
+    Teardown,
+    Fixture,
     Test(TestAttribute),
-    ShouldPanic,
-    Ignore,
-    Timeout(TimeoutAttribute),
+    Bench(BenchAttribute),
+    ShouldPanic(ShouldPanicAttribute),
+    ShouldReset(ShouldResetAttribute),
+    ShouldFault(ShouldFaultAttribute),
+    Ignore(IgnoreAttribute),
+    IgnoreUnless(syn::Meta),
+    Timeout(Timeout),
+    Retry(u32),
+    Repeat(u32),
+    TestCase(TestCaseAttribute),
+    Instantiate(Vec<syn::Type>),
+    Tag(Vec<String>),
+    Meta(Vec<(String, String)>),
 }
 
+/// Parses a recognized attribute, aborting with a compiler error if it is malformed
+type ParseAttribute = fn(&Attribute) -> FuncAttribute;
+
+/// Attributes which are recognized on the functions of a test module, with their parser
+const ATTRIBUTES: &[(&str, ParseAttribute)] = &[
+    ("init", |_| FuncAttribute::Init),
+    ("group_init", |_| FuncAttribute::GroupInit),
+    ("teardown", |_| FuncAttribute::Teardown),
+    ("fixture", |_| FuncAttribute::Fixture),
+    ("test", |attr| {
+        FuncAttribute::Test(TestAttribute::from_attr(attr))
+    }),
+    ("bench", |attr| {
+        FuncAttribute::Bench(BenchAttribute::from_attr(attr))
+    }),
+    ("should_panic", |attr| {
+        FuncAttribute::ShouldPanic(ShouldPanicAttribute::from_attr(attr))
+    }),
+    ("should_reset", |attr| {
+        FuncAttribute::ShouldReset(ShouldResetAttribute::from_attr(attr))
+    }),
+    ("should_fault", |attr| {
+        FuncAttribute::ShouldFault(ShouldFaultAttribute::from_attr(attr))
+    }),
+    ("ignore", |attr| {
+        FuncAttribute::Ignore(IgnoreAttribute::from_attr(attr))
+    }),
+    ("ignore_unless", |attr| {
+        FuncAttribute::IgnoreUnless(parse_ignore_unless(attr))
+    }),
+    ("timeout", |attr| {
+        FuncAttribute::Timeout(Timeout::from_attr(attr))
+    }),
+    ("retry", |attr| {
+        FuncAttribute::Retry(parse_count(attr, "the number of retries"))
+    }),
+    ("repeat", |attr| {
+        FuncAttribute::Repeat(parse_count(attr, "the number of repetitions"))
+    }),
+    ("test_case", |attr| {
+        FuncAttribute::TestCase(TestCaseAttribute::from_attr(attr))
+    }),
+    ("instantiate", |attr| {
+        FuncAttribute::Instantiate(parse_instantiate(attr))
+    }),
+    ("tag", |attr| FuncAttribute::Tag(parse_tags(attr))),
+    ("meta", |attr| FuncAttribute::Meta(parse_meta(attr))),
+];
+
+/// Names of the attributes which are recognized on the functions of a test module
+pub(crate) fn attribute_names() -> impl Iterator<Item = &'static str> {
+    ATTRIBUTES.iter().map(|(name, _)| *name)
+}
+
+/// Built-in attributes, which are never reported as a misspelled attribute
+const BUILTIN_ATTRIBUTE_NAMES: &[&str] = &[
+    "allow",
+    "cfg",
+    "cfg_attr",
+    "cold",
+    "deny",
+    "deprecated",
+    "doc",
+    "expect",
+    "export_name",
+    "forbid",
+    "inline",
+    "link_section",
+    "must_use",
+    "no_mangle",
+    "repr",
+    "target_feature",
+    "track_caller",
+    "used",
+    "warn",
+];
+
 impl FuncAttribute {
     /// Tries to convert a `syn::Attribute` into a `FuncAttribute`.
     /// If the attribute is recognized, it returns `Some(FuncAttribute)`, otherwise `None`.
     /// Aborts with a compiler error if the attribute is malformed.
     fn try_from_attr(attr: &Attribute) -> Option<Self> {
         let ident = attr.path().get_ident()?.to_string();
-        Some(match ident.as_str() {
-            "init" => FuncAttribute::Init,
-            "test" => FuncAttribute::Test(TestAttribute::from_attr(attr)),
-            "should_panic" => FuncAttribute::ShouldPanic,
-            "ignore" => FuncAttribute::Ignore,
-            "timeout" => FuncAttribute::Timeout(TimeoutAttribute::from_attr(attr)),
-            _ => return None,
-        })
+        let (_, parse) = ATTRIBUTES.iter().find(|(name, _)| *name == ident)?;
+        Some(parse(attr))
+    }
+}
+
+/// Aborts if the attribute looks like a misspelled attribute of embedded-test (e.g. `#[timout]`),
+/// which would otherwise end up as an unknown attribute on the function.
+fn check_misspelled(attr: &Attribute) {
+    let Some(ident) = attr.path().get_ident().map(|i| i.to_string()) else {
+        return;
+    };
+    if BUILTIN_ATTRIBUTE_NAMES.contains(&ident.as_str()) {
+        return;
+    }
+    // Similar to rustc's suggestions: at most one edit per three characters
+    let max_distance = ident.len().max(3) / 3;
+    let suggestion = attribute_names()
+        .map(|name| (name, edit_distance(&ident, name)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance);
+    if let Some((name, _)) = suggestion {
+        abort!(
+            attr.path(),
+            "unknown attribute `{}`, did you mean `#[{}]`?",
+            ident,
+            name
+        );
+    }
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// Parses the count of `#[retry(3)]` or `#[repeat(3)]`
+fn parse_count(attr: &Attribute, description: &str) -> u32 {
+    let name = attr.path().get_ident().unwrap();
+    match attr
+        .parse_args::<syn::LitInt>()
+        .and_then(|value| value.base10_parse::<u32>())
+    {
+        Ok(count) => count,
+        Err(e) => abort!(
+            attr,
+            "failed to parse `{}` attribute. Must be of the form #[{}(3)] where 3 is {}. Error: {}",
+            name,
+            name,
+            description,
+            e
+        ),
+    }
+}
+
+/// `#[ignore]` or `#[ignore = "reason"]`
+pub(crate) struct IgnoreAttribute {
+    pub reason: Option<String>,
+}
+
+impl IgnoreAttribute {
+    fn from_attr(attr: &Attribute) -> Self {
+        match &attr.meta {
+            syn::Meta::Path(_) => IgnoreAttribute { reason: None },
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(reason),
+                        ..
+                    }),
+                ..
+            }) => IgnoreAttribute {
+                reason: Some(reason.value()),
+            },
+            _ => abort!(
+                attr,
+                "failed to parse `ignore` attribute. Must be of the form #[ignore] or #[ignore = \"reason\"]"
+            ),
+        }
+    }
+}
+
+/// Parses the cfg predicate of `#[ignore_unless(<predicate>)]`, e.g. `#[ignore_unless(feature = "x")]`
+fn parse_ignore_unless(attr: &Attribute) -> syn::Meta {
+    match attr.parse_args::<syn::Meta>() {
+        Ok(predicate) => predicate,
+        Err(e) => abort!(
+            attr,
+            "failed to parse `ignore_unless` attribute. Must be of the form #[ignore_unless(<cfg predicate>)], e.g. #[ignore_unless(feature = \"x\")]. Error: {}",
+            e
+        ),
+    }
+}
+
+/// Parses the types of `#[instantiate(Type1, Type2, ...)]`
+fn parse_instantiate(attr: &Attribute) -> Vec<syn::Type> {
+    match attr.parse_args_with(
+        syn::punctuated::Punctuated::<syn::Type, syn::Token![,]>::parse_terminated,
+    ) {
+        Ok(types) if !types.is_empty() => types.into_iter().collect(),
+        Ok(_) => abort!(
+            attr,
+            "failed to parse `instantiate` attribute. Must be of the form #[instantiate(Type1, Type2)]. Error: at least one type is required"
+        ),
+        Err(e) => abort!(
+            attr,
+            "failed to parse `instantiate` attribute. Must be of the form #[instantiate(Type1, Type2)]. Error: {}",
+            e
+        ),
+    }
+}
+
+/// Parses `#[tag("tag1", "tag2", ...)]`
+fn parse_tags(attr: &Attribute) -> Vec<String> {
+    match attr.parse_args_with(
+        syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated,
+    ) {
+        Ok(tags) => tags.iter().map(|tag| tag.value()).collect(),
+        Err(e) => abort!(
+            attr,
+            "failed to parse `tag` attribute. Must be of the form #[tag(\"tag1\", \"tag2\")]. Error: {}",
+            e
+        ),
     }
 }
 
-pub(crate) struct TimeoutAttribute {
-    pub value: u32,
+/// Parses `#[meta(key1 = "value1", key2 = "value2", ...)]`
+fn parse_meta(attr: &Attribute) -> Vec<(String, String)> {
+    let error = |e: &dyn std::fmt::Display| -> ! {
+        abort!(
+            attr,
+            "failed to parse `meta` attribute. Must be of the form #[meta(key = \"value\")]. Error: {}",
+            e
+        )
+    };
+    let entries = match attr.parse_args_with(
+        syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated,
+    ) {
+        Ok(entries) => entries,
+        Err(e) => error(&e),
+    };
+
+    entries
+        .iter()
+        .map(|entry| {
+            let Some(key) = entry.path.get_ident() else {
+                error(&"key must be an identifier")
+            };
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(value),
+                ..
+            }) = &entry.value
+            else {
+                error(&"value must be a string literal")
+            };
+            (key.to_string(), value.value())
+        })
+        .collect()
+}
+
+/// `#[test_case(arg1, arg2, ...)]` or `#[test_case(arg1, arg2, ...; "name")]`
+pub(crate) struct TestCaseAttribute {
+    pub args: Vec<syn::Expr>,
+    pub name: Option<syn::LitStr>,
 }
 
-impl syn::parse::Parse for TimeoutAttribute {
+impl syn::parse::Parse for TestCaseAttribute {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let value_lit: syn::LitInt = input.parse()?;
-        let value = value_lit.base10_parse::<u32>()?;
+        let mut args = vec![];
+        while !input.is_empty() && !input.peek(syn::Token![;]) {
+            args.push(input.parse::<syn::Expr>()?);
+            if input.is_empty() || input.peek(syn::Token![;]) {
+                break;
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
 
-        Ok(TimeoutAttribute { value })
+        let name = if input.peek(syn::Token![;]) {
+            input.parse::<syn::Token![;]>()?;
+            Some(input.parse::<syn::LitStr>()?)
+        } else {
+            None
+        };
+
+        Ok(TestCaseAttribute { args, name })
     }
 }
 
-impl TimeoutAttribute {
+impl TestCaseAttribute {
     fn from_attr(attr: &Attribute) -> Self {
-        match attr.parse_args::<TimeoutAttribute>() {
-            Ok(timeout_attr) => timeout_attr,
+        if let syn::Meta::Path(_) = &attr.meta {
+            return TestCaseAttribute {
+                args: vec![],
+                name: None,
+            };
+        }
+        match attr.parse_args::<TestCaseAttribute>() {
+            Ok(test_case_attr) => test_case_attr,
             Err(e) => {
                 abort!(
                     attr,
-                    "failed to parse `timeout` attribute. Must be of the form #[timeout(10)] where 10 is the timeout in seconds. Error: {}",
+                    "failed to parse `test_case` attribute. Must be of the form #[test_case(arg1, arg2)] or #[test_case(arg1, arg2; \"name\")]. Error: {}",
                     e
                 );
             }
@@ -57,10 +332,119 @@ impl TimeoutAttribute {
     }
 }
 
+/// `#[should_panic]`, `#[should_panic(expected = "...")]` or `#[should_panic = "..."]`
+#[derive(Debug, FromMeta, Default)]
+pub(crate) struct ShouldPanicAttribute {
+    #[darling(default)]
+    pub expected: Option<String>,
+}
+
+impl ShouldPanicAttribute {
+    fn from_attr(attr: &Attribute) -> Self {
+        match &attr.meta {
+            syn::Meta::Path(_) => ShouldPanicAttribute::default(),
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(expected),
+                        ..
+                    }),
+                ..
+            }) => ShouldPanicAttribute {
+                expected: Some(expected.value()),
+            },
+            meta => match ShouldPanicAttribute::from_meta(meta) {
+                Ok(should_panic_attr) => should_panic_attr,
+                Err(e) => abort!(attr, "failed to parse `should_panic` attribute. Must be of the form #[should_panic] or #[should_panic(expected = \"message\")]: {}", e),
+            },
+        }
+    }
+}
+
+/// `#[should_reset]` or `#[should_reset(within = 2s)]`
+#[derive(Debug, FromMeta, Default)]
+pub(crate) struct ShouldResetAttribute {
+    /// Time in which the device has to reset, checked by the runner
+    #[darling(default)]
+    pub within: Option<Timeout>,
+}
+
+impl ShouldResetAttribute {
+    fn from_attr(attr: &Attribute) -> Self {
+        match &attr.meta {
+            syn::Meta::Path(_) => ShouldResetAttribute::default(),
+            meta => match ShouldResetAttribute::from_meta(meta) {
+                Ok(should_reset_attr) => should_reset_attr,
+                Err(e) => abort!(attr, "failed to parse `should_reset` attribute. Must be of the form #[should_reset] or #[should_reset(within = 2s)]: {}", e),
+            },
+        }
+    }
+}
+
+/// Fault classes which can be expected via `#[should_fault(kind = "...")]`
+pub(crate) const FAULT_KINDS: &[&str] = &[
+    // Cortex-M
+    "HardFault",
+    "MemManage",
+    "BusFault",
+    "UsageFault",
+    // RISC-V
+    "InstructionMisaligned",
+    "InstructionFault",
+    "IllegalInstruction",
+    "Breakpoint",
+    "LoadMisaligned",
+    "LoadFault",
+    "StoreMisaligned",
+    "StoreFault",
+    "UserEnvCall",
+    "SupervisorEnvCall",
+    "MachineEnvCall",
+    "InstructionPageFault",
+    "LoadPageFault",
+    "StorePageFault",
+];
+
+/// `#[should_fault]` or `#[should_fault(kind = "MemManage")]`
+#[derive(Debug, FromMeta, Default)]
+pub(crate) struct ShouldFaultAttribute {
+    #[darling(default)]
+    pub kind: Option<syn::LitStr>,
+}
+
+impl ShouldFaultAttribute {
+    fn from_attr(attr: &Attribute) -> Self {
+        let should_fault_attr = match &attr.meta {
+            syn::Meta::Path(_) => ShouldFaultAttribute::default(),
+            meta => match ShouldFaultAttribute::from_meta(meta) {
+                Ok(should_fault_attr) => should_fault_attr,
+                Err(e) => abort!(attr, "failed to parse `should_fault` attribute. Must be of the form #[should_fault] or #[should_fault(kind = \"MemManage\")]: {}", e),
+            },
+        };
+        if let Some(kind) = &should_fault_attr.kind {
+            if !FAULT_KINDS.contains(&kind.value().as_str()) {
+                abort!(
+                    kind,
+                    "unknown fault kind `{}`, expected one of {}",
+                    kind.value(),
+                    FAULT_KINDS.join(", ")
+                );
+            }
+        }
+        should_fault_attr
+    }
+}
+
 #[derive(Debug, FromMeta, Default)]
 pub(crate) struct TestAttribute {
     #[darling(default)]
     pub init: Option<syn::Ident>,
+    /// Executor which runs this test instead of the executor of the module
+    #[darling(default)]
+    pub executor: Option<syn::Expr>,
+    /// Number of stages of a test which spans device resets
+    #[darling(default)]
+    pub stages: Option<u32>,
 }
 
 impl TestAttribute {
@@ -69,7 +453,30 @@ impl TestAttribute {
             syn::Meta::Path(_) => TestAttribute::default(),
             meta => match TestAttribute::from_meta(meta) {
                 Ok(test_attr) => test_attr,
-                Err(e) => abort!(attr, "failed to parse `test` attribute. Must be of the form #[test(init = init_function)]: {}", e),
+                Err(e) => abort!(attr, "failed to parse `test` attribute. Must be of the form #[test(init = init_function, executor = Executor::new(), stages = 2)] (each argument is optional): {}", e),
+            },
+        }
+    }
+}
+
+/// `#[bench]` or `#[bench(iterations = 100, warmup = 10)]`
+#[derive(Debug, FromMeta, Default)]
+pub(crate) struct BenchAttribute {
+    /// Number of runs of the closure before the measurement starts
+    #[darling(default)]
+    pub warmup: Option<u32>,
+    /// Number of measured runs of the closure
+    #[darling(default)]
+    pub iterations: Option<u32>,
+}
+
+impl BenchAttribute {
+    fn from_attr(attr: &Attribute) -> Self {
+        match &attr.meta {
+            syn::Meta::Path(_) => BenchAttribute::default(),
+            meta => match BenchAttribute::from_meta(meta) {
+                Ok(bench_attr) => bench_attr,
+                Err(e) => abort!(attr, "failed to parse `bench` attribute. Must be of the form #[bench(iterations = 100, warmup = 10)] (each argument is optional): {}", e),
             },
         }
     }
@@ -90,6 +497,7 @@ impl From<ItemFn> for FunctionWithAttributes {
                 attributes.push((func_attr, attr.path().span()));
                 false
             } else {
+                check_misspelled(attr);
                 true
             }
         });
@@ -1,11 +1,18 @@
+use crate::attributes::tests::parse::{ShouldPanicAttribute, Timeout};
 use darling::ast::NestedMeta;
+use darling::util::Override;
 use darling::FromMeta;
 use proc_macro::TokenStream;
 
-#[derive(Debug, FromMeta)]
+#[derive(Debug, Clone, Default, FromMeta)]
 pub(crate) struct MacroArgs {
     pub executor: Option<syn::Expr>,
-    pub default_timeout: Option<u32>,
+    pub default_timeout: Option<Timeout>,
+    pub default_retry: Option<u32>,
+    pub default_repeat: Option<u32>,
+    /// Tags which are inherited by all tests in the module
+    #[darling(default)]
+    pub tags: Vec<syn::LitStr>,
 }
 
 impl MacroArgs {
@@ -15,3 +22,23 @@ impl MacroArgs {
         Ok(macro_args)
     }
 }
+
+/// Arguments of a freestanding `#[embedded_test::test(...)]` function
+#[derive(Debug, FromMeta)]
+pub(crate) struct TestMacroArgs {
+    /// Function which is called to create the state passed to the test
+    pub init: Option<syn::Path>,
+    pub timeout: Option<Timeout>,
+    /// `ignore` or `ignore = "reason"`
+    pub ignore: Option<Override<String>>,
+    /// `should_panic` or `should_panic(expected = "message")`
+    pub should_panic: Option<Override<ShouldPanicAttribute>>,
+}
+
+impl TestMacroArgs {
+    pub(crate) fn parse(args: TokenStream) -> Result<Self, syn::Error> {
+        let attr_args = NestedMeta::parse_meta_list(args.into())?;
+        let macro_args = TestMacroArgs::from_list(&attr_args)?;
+        Ok(macro_args)
+    }
+}
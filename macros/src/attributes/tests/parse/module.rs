@@ -1,10 +1,14 @@
 use crate::attributes::tests::parse::FunctionWithAttributes;
 use proc_macro_error3::abort;
-use syn::{Item, ItemMod};
+use syn::{Attribute, Item, ItemMod, Visibility};
 
 pub(crate) struct Module {
     pub name: String,
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
     pub functions: Vec<FunctionWithAttributes>,
+    /// Nested inline modules, which can contain tests as well
+    pub submodules: Vec<Module>,
     pub untouched_tokens: Vec<Item>,
 }
 
@@ -16,16 +20,21 @@ impl From<ItemMod> for Module {
 
         let mut untouched_tokens = vec![];
         let mut functions = vec![];
+        let mut submodules = vec![];
         for item in items {
             match item {
                 Item::Fn(f) => functions.push(FunctionWithAttributes::from(f)),
+                Item::Mod(m) if m.content.is_some() => submodules.push(Module::from(m)),
                 _ => untouched_tokens.push(item),
             }
         }
 
         Self {
             name: module.ident.to_string(),
+            attrs: module.attrs,
+            vis: module.vis,
             functions,
+            submodules,
             untouched_tokens,
         }
     }
@@ -0,0 +1,106 @@
+use darling::ast::NestedMeta;
+use darling::FromMeta;
+use proc_macro_error3::abort;
+use syn::{Attribute, Lit, Meta};
+
+/// A timeout, given either as plain number of seconds (`10`), as duration literal (`250ms`, `2s`, `"2min"`)
+/// or in a specific unit (`millis = 250`, `secs = 2`).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Timeout {
+    pub millis: u32,
+}
+
+impl Timeout {
+    /// Whole seconds (rounded up), for runners which only know the `timeout` field in seconds
+    pub fn secs(&self) -> u32 {
+        self.millis.div_ceil(1000)
+    }
+
+    /// Parses `#[timeout(...)]`
+    pub fn from_attr(attr: &Attribute) -> Self {
+        match Timeout::from_meta(&attr.meta) {
+            Ok(timeout) => timeout,
+            Err(e) => abort!(
+                attr,
+                "failed to parse `timeout` attribute. Must be of the form #[timeout(10)] where 10 is the timeout in seconds, #[timeout(250ms)], #[timeout(\"2min\")] or #[timeout(millis = 250)]. Error: {}",
+                e
+            ),
+        }
+    }
+}
+
+/// Converts a number with a unit (`ms`, `s` or `min`) into milliseconds. No unit means seconds.
+fn to_millis(number: &str, unit: &str) -> darling::Result<u32> {
+    let number: u32 = number
+        .parse()
+        .map_err(|_| darling::Error::custom(format!("invalid number `{}`", number)))?;
+    let factor = match unit {
+        "ms" => 1,
+        "" | "s" => 1000,
+        "min" => 60 * 1000,
+        unit => {
+            return Err(darling::Error::custom(format!(
+                "unknown unit `{}`, expected one of `ms`, `s` or `min`",
+                unit
+            )))
+        }
+    };
+    number
+        .checked_mul(factor)
+        .ok_or_else(|| darling::Error::custom("timeout is too large"))
+}
+
+impl FromMeta for Timeout {
+    fn from_value(value: &Lit) -> darling::Result<Self> {
+        let millis = match value {
+            Lit::Int(lit) => to_millis(lit.base10_digits(), lit.suffix())?,
+            Lit::Str(lit) => {
+                let value = lit.value();
+                let value = value.trim();
+                let (number, unit) = value.split_at(
+                    value
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(value.len()),
+                );
+                to_millis(number, unit.trim())?
+            }
+            _ => return Err(darling::Error::unexpected_lit_type(value)),
+        };
+        Ok(Timeout { millis })
+    }
+
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        // `timeout(250ms)`
+        if let [NestedMeta::Lit(lit)] = items {
+            return Self::from_value(lit);
+        }
+        // e.g. `timeout(None)` or `timeout(TIMEOUT)`, which would otherwise be reported as unknown field
+        if let [NestedMeta::Meta(Meta::Path(_))] = items {
+            return Err(darling::Error::custom(
+                "expected a duration literal such as `5`, `500ms` or `\"2s\"`",
+            ));
+        }
+
+        // `timeout(millis = 250)` or `timeout(secs = 2)`
+        #[derive(FromMeta)]
+        struct Units {
+            millis: Option<u32>,
+            secs: Option<u32>,
+        }
+        match Units::from_list(items)? {
+            Units {
+                millis: Some(millis),
+                secs: None,
+            } => Ok(Timeout { millis }),
+            Units {
+                millis: None,
+                secs: Some(secs),
+            } => Ok(Timeout {
+                millis: to_millis(&secs.to_string(), "s")?,
+            }),
+            _ => Err(darling::Error::custom(
+                "exactly one of `millis` or `secs` must be given",
+            )),
+        }
+    }
+}
@@ -1,86 +1,597 @@
-use crate::attributes::tests::parse::{FuncAttribute, FunctionWithAttributes};
+use crate::attributes::tests::parse::{FuncAttribute, FunctionWithAttributes, Timeout};
 use proc_macro_error3::abort;
 use syn::{Attribute, ItemFn, ReturnType, Type};
 
+#[derive(Clone)]
 pub(crate) struct InitFunc {
     pub name: String,
     pub func: ItemFn,
+    /// The state returned by the init function (the `Ok` type, if it returns a `Result`)
     pub state: Option<Type>,
+    /// Whether the init function returns `Result<State, E>`
+    pub fallible: bool,
+    /// Position of an `info: &TestInfo` parameter
+    pub info_param: Option<usize>,
+    /// Position of a `spawner: Spawner` parameter
+    pub spawner_param: Option<usize>,
     pub asyncness: bool,
+    /// Number of module levels above the test module, in which the function is defined (0 if defined in the same module)
+    pub parent_depth: usize,
 }
 
 impl From<FunctionWithAttributes> for InitFunc {
     fn from(func: FunctionWithAttributes) -> Self {
         let FunctionWithAttributes { func, attributes } = func;
+        // Also used for `#[group_init]` and custom init functions (which have no attribute)
+        let kind = if attributes
+            .iter()
+            .any(|(attr, _)| matches!(attr, FuncAttribute::GroupInit))
+        {
+            "group_init"
+        } else {
+            "init"
+        };
         for (attr, span) in attributes {
             match attr {
-                FuncAttribute::Init => {}
-                FuncAttribute::Test(_) => unreachable!(),
-                _ => abort!(span, "The `#[init]` function can not have this attribute"),
+                FuncAttribute::Init | FuncAttribute::GroupInit => {}
+                FuncAttribute::Test(_)
+                | FuncAttribute::Bench(_)
+                | FuncAttribute::Teardown
+                | FuncAttribute::Fixture => unreachable!(),
+                _ => abort!(
+                    span,
+                    "The `#[{}]` function can not have this attribute",
+                    kind
+                ),
             }
         }
-        if check_fn_sig(&func.sig).is_err() || !func.sig.inputs.is_empty() {
+        let (info_param, inputs) = split_info_param(&func);
+        let (spawner_param, inputs) = split_spawner_param(&func, inputs);
+        if check_fn_sig(&func.sig).is_err() || !inputs.is_empty() {
             abort!(
                 func.sig,
-                "`#[init]` function must have signature `async fn() [-> Type]` (async/return type are optional)",
+                "`#[{}]` function must have signature `async fn([info: &TestInfo], [spawner: Spawner]) [-> Type]` (async/parameters/return type are optional)",
+                kind
             );
         }
 
         if cfg!(not(feature = "embassy")) && func.sig.asyncness.is_some() {
             abort!(
                 func.sig,
-                "`#[init]` function can only be async if an async executor is enabled via feature",
+                "`#[{}]` function can only be async if an async executor is enabled via feature",
+                kind
             );
         }
 
-        let state = match &func.sig.output {
-            ReturnType::Default => None,
-            ReturnType::Type(.., ty) => Some(*ty.clone()),
+        let (state, fallible) = match &func.sig.output {
+            ReturnType::Default => (None, false),
+            ReturnType::Type(.., ty) => match result_ok_type(ty) {
+                Some(Type::Tuple(unit)) if unit.elems.is_empty() => (None, true),
+                Some(ok) => (Some(ok), true),
+                None => (Some(*ty.clone()), false),
+            },
         };
         InitFunc {
             name: func.sig.ident.to_string(),
             asyncness: func.sig.asyncness.is_some(),
             func,
             state,
+            fallible,
+            info_param,
+            spawner_param,
+            parent_depth: 0,
+        }
+    }
+}
+
+/// Returns `T` if the type is a `Result<T, E>` (or an alias like `io::Result<T>`)
+fn result_ok_type(ty: &Type) -> Option<Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(ok) => Some(ok.clone()),
+        _ => None,
+    }
+}
+
+impl InitFunc {
+    /// The init function as seen from a nested module
+    pub fn inherited(&self) -> Self {
+        InitFunc {
+            parent_depth: self.parent_depth + 1,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct TeardownFunc {
+    pub func: ItemFn,
+    pub state: Option<Type>,
+    /// Position of an `info: &TestInfo` parameter
+    pub info_param: Option<usize>,
+    pub asyncness: bool,
+    /// Number of module levels above the test module, in which the function is defined (0 if defined in the same module)
+    pub parent_depth: usize,
+}
+
+impl From<FunctionWithAttributes> for TeardownFunc {
+    fn from(func: FunctionWithAttributes) -> Self {
+        let FunctionWithAttributes { func, attributes } = func;
+        for (attr, span) in attributes {
+            match attr {
+                FuncAttribute::Teardown => {}
+                FuncAttribute::Init
+                | FuncAttribute::GroupInit
+                | FuncAttribute::Test(_)
+                | FuncAttribute::Bench(_)
+                | FuncAttribute::Fixture => unreachable!(),
+                _ => abort!(
+                    span,
+                    "The `#[teardown]` function can not have this attribute"
+                ),
+            }
+        }
+        let (info_param, inputs) = split_info_param(&func);
+        if check_fn_sig(&func.sig).is_err() {
+            abort!(
+                func.sig,
+                "`#[teardown]` function must have signature `async fn(state: Type, outcome: embedded_test::Outcome) [-> Type]` (async/parameters/return type are optional)",
+            );
+        }
+
+        if cfg!(not(feature = "embassy")) && func.sig.asyncness.is_some() {
+            abort!(
+                func.sig,
+                "`#[teardown]` function can only be async if an async executor is enabled via feature",
+            );
+        }
+
+        // The outcome is recognized by its type (so that `fn teardown(state: Board)` takes the state), it comes last
+        let state = match (inputs.as_slice(), inputs.iter().position(|arg| is_outcome(arg))) {
+            ([], _) | ([_], Some(0)) => None,
+            // NOTE we cannot check the argument type matches `init.state` at this point
+            ([state], None) | ([state, _], Some(1)) => Some(extract_single_value_arg(state)),
+            _ => abort!(
+                func.sig,
+                "`#[teardown]` function must have signature `async fn(state: Type, outcome: embedded_test::Outcome) [-> Type]` (async/parameters/return type are optional)",
+            ),
+        };
+
+        TeardownFunc {
+            asyncness: func.sig.asyncness.is_some(),
+            func,
+            state,
+            info_param,
+            parent_depth: 0,
         }
     }
 }
 
+impl TeardownFunc {
+    /// The teardown function as seen from a nested module
+    pub fn inherited(&self) -> Self {
+        TeardownFunc {
+            parent_depth: self.parent_depth + 1,
+            ..self.clone()
+        }
+    }
+
+    /// Returns whether the teardown function wants to receive the outcome of the test
+    pub fn takes_outcome(&self) -> bool {
+        self.func.sig.inputs.iter().any(is_outcome)
+    }
+}
+
+/// A `#[fixture]` function, which is called for every test taking a parameter of the same name
+#[derive(Clone)]
+pub(crate) struct FixtureFunc {
+    pub name: String,
+    pub func: ItemFn,
+    /// The fixtures this fixture depends on (i.e. its parameters)
+    pub deps: Vec<FixtureParam>,
+    pub asyncness: bool,
+    /// Number of module levels above the test module, in which the function is defined (0 if defined in the same module)
+    pub parent_depth: usize,
+}
+
+/// A parameter which is injected by the fixture of the same name
+#[derive(Clone)]
+pub(crate) struct FixtureParam {
+    pub name: syn::Ident,
+    pub ty: Type,
+}
+
+impl From<FunctionWithAttributes> for FixtureFunc {
+    fn from(func: FunctionWithAttributes) -> Self {
+        let FunctionWithAttributes { func, attributes } = func;
+        for (attr, span) in attributes {
+            match attr {
+                FuncAttribute::Fixture => {}
+                FuncAttribute::Init
+                | FuncAttribute::GroupInit
+                | FuncAttribute::Teardown
+                | FuncAttribute::Test(_)
+                | FuncAttribute::Bench(_) => unreachable!(),
+                _ => abort!(
+                    span,
+                    "The `#[fixture]` function can not have this attribute"
+                ),
+            }
+        }
+        if check_fn_sig(&func.sig).is_err() || matches!(func.sig.output, ReturnType::Default) {
+            abort!(
+                func.sig,
+                "`#[fixture]` function must have signature `async fn(fixture: Type, ..) -> Type` (async/parameters are optional)",
+            );
+        }
+
+        if cfg!(not(feature = "embassy")) && func.sig.asyncness.is_some() {
+            abort!(
+                func.sig,
+                "`#[fixture]` function can only be async if an async executor is enabled via feature",
+            );
+        }
+
+        let deps = func
+            .sig
+            .inputs
+            .iter()
+            .map(|arg| {
+                fixture_param(arg).unwrap_or_else(|| {
+                    abort!(
+                        arg,
+                        "parameters of a `#[fixture]` function must be named after the fixtures it depends on"
+                    )
+                })
+            })
+            .collect();
+
+        FixtureFunc {
+            name: func.sig.ident.to_string(),
+            asyncness: func.sig.asyncness.is_some(),
+            func,
+            deps,
+            parent_depth: 0,
+        }
+    }
+}
+
+impl FixtureFunc {
+    /// The fixture as seen from a nested module
+    pub fn inherited(&self) -> Self {
+        FixtureFunc {
+            parent_depth: self.parent_depth + 1,
+            ..self.clone()
+        }
+    }
+}
+
+/// Returns the name and type of a parameter like `uart: Uart`
+fn fixture_param(arg: &syn::FnArg) -> Option<FixtureParam> {
+    let syn::FnArg::Typed(pat) = arg else {
+        return None;
+    };
+    let syn::Pat::Ident(ident) = &*pat.pat else {
+        return None;
+    };
+    Some(FixtureParam {
+        name: ident.ident.clone(),
+        ty: *pat.ty.clone(),
+    })
+}
+
+/// A single instance of a parameterized test, generated from a `#[test_case(...)]` and/or `#[instantiate(...)]` attribute
+#[derive(Clone)]
+pub(crate) struct TestCase {
+    /// Suffix appended to the test name (e.g. `case_1`, `<u8>` or the user provided name)
+    pub name: String,
+    pub args: Vec<syn::Expr>,
+    /// Type to instantiate a generic test function with
+    pub type_arg: Option<Type>,
+}
+
+/// Number of warm-up and measured runs of the closure of a `#[bench]` function
+#[derive(Clone, Copy)]
+pub(crate) struct Bench {
+    pub warmup: u32,
+    pub iterations: u32,
+}
+
+impl Bench {
+    const DEFAULT_WARMUP: u32 = 10;
+    const DEFAULT_ITERATIONS: u32 = 100;
+    /// The cycles of every iteration are kept on the target, to compute the median (see `embedded_test::Bencher`)
+    const MAX_ITERATIONS: u32 = 256;
+}
+
+#[derive(Clone)]
 pub(crate) struct TestFunc {
     pub func: ItemFn,
     pub cfgs: Vec<Attribute>,
     pub input: Option<Type>,
+    /// Parameters injected by fixtures, together with their position in the parameter list
+    pub fixtures: Vec<(usize, FixtureParam)>,
+    /// Position of an `info: &TestInfo` parameter
+    pub info_param: Option<usize>,
+    /// Position of a `spawner: Spawner` parameter
+    pub spawner_param: Option<usize>,
+    /// Position of the `bencher: &mut Bencher` parameter of a `#[bench]` function
+    pub bencher_param: Option<usize>,
+    pub cases: Vec<TestCase>,
     pub should_panic: bool,
+    /// Substring which must be contained in the panic message (`#[should_panic(expected = "...")]`)
+    pub expected_panic: Option<String>,
+    /// `#[should_reset]`, optionally with the time in which the device has to reset
+    pub should_reset: Option<Option<Timeout>>,
+    /// `#[should_fault]`, optionally with the expected fault class (e.g. `MemManage`)
+    pub should_fault: Option<Option<String>>,
     pub ignore: bool,
+    pub ignore_reason: Option<String>,
+    /// cfg predicate of `#[ignore_unless(...)]`. The test is listed as ignored if it does not hold.
+    pub ignore_unless: Option<syn::Meta>,
     pub asyncness: bool,
-    pub timeout: Option<u32>,
+    pub timeout: Option<Timeout>,
+    /// Number of times the runner should re-run the test if it fails
+    pub retry: Option<u32>,
+    /// Number of times the test should be run
+    pub repeat: Option<u32>,
     pub custom_init: Option<syn::Ident>,
+    /// Executor of `#[test(executor = ...)]`, which overrides the executor of the module
+    pub executor: Option<syn::Expr>,
+    /// Number of stages of `#[test(stages = n)]`. The device is reset between the stages.
+    pub stages: Option<u32>,
+    /// Set for a `#[bench]` function, which measures the cycles of a closure instead of being a test
+    pub bench: Option<Bench>,
+    /// Init function of a freestanding `#[embedded_test::test(init = path::to::init)]`
+    pub init_path: Option<syn::Path>,
+    pub tags: Vec<String>,
+    /// Key-value metadata from `#[meta(key = "value")]`
+    pub meta: Vec<(String, String)>,
 }
 
 impl From<FunctionWithAttributes> for TestFunc {
     fn from(func: FunctionWithAttributes) -> Self {
+        TestFunc::new(func, &[])
+    }
+}
+
+impl TestFunc {
+    /// Parameters named after one of the `fixtures` are injected by that fixture,
+    /// the remaining ones are the (optional) state and the arguments of the test cases.
+    pub fn new(func: FunctionWithAttributes, fixtures: &[String]) -> Self {
         let FunctionWithAttributes { func, attributes } = func;
         let mut should_panic = false;
+        let mut expected_panic = None;
+        let mut should_reset = None;
+        let mut should_fault = None;
         let mut ignore = false;
+        let mut ignore_reason = None;
+        let mut ignore_unless = None;
         let mut timeout = None;
+        let mut retry = None;
+        let mut repeat = None;
         let mut custom_init = None;
-        for (attr, _span) in attributes {
+        let mut executor = None;
+        let mut stages = None;
+        let mut bench = None;
+        let mut cases: Vec<TestCase> = vec![];
+        let mut instantiate: Vec<Type> = vec![];
+        let mut tags: Vec<String> = vec![];
+        let mut meta: Vec<(String, String)> = vec![];
+        for (attr, span) in attributes {
             match attr {
-                FuncAttribute::Init => unreachable!(),
-                FuncAttribute::Test(attr) => custom_init = attr.init,
-                FuncAttribute::ShouldPanic => should_panic = true,
-                FuncAttribute::Ignore => ignore = true,
-                FuncAttribute::Timeout(t) => timeout = Some(t.value),
+                FuncAttribute::Init
+                | FuncAttribute::GroupInit
+                | FuncAttribute::Teardown
+                | FuncAttribute::Fixture => unreachable!(),
+                FuncAttribute::Test(attr) => {
+                    custom_init = attr.init;
+                    executor = attr.executor;
+                    if attr.stages.is_some_and(|n| n < 2) {
+                        abort!(span, "a multi-stage test must have at least 2 `stages`");
+                    }
+                    stages = attr.stages;
+                }
+                FuncAttribute::Bench(attr) => {
+                    let iterations = attr.iterations.unwrap_or(Bench::DEFAULT_ITERATIONS);
+                    if !(1..=Bench::MAX_ITERATIONS).contains(&iterations) {
+                        abort!(
+                            span,
+                            "a `#[bench]` must have between 1 and {} `iterations`",
+                            Bench::MAX_ITERATIONS
+                        );
+                    }
+                    bench = Some(Bench {
+                        warmup: attr.warmup.unwrap_or(Bench::DEFAULT_WARMUP),
+                        iterations,
+                    });
+                }
+                FuncAttribute::ShouldPanic(attr) => {
+                    should_panic = true;
+                    expected_panic = attr.expected;
+                }
+                FuncAttribute::ShouldReset(attr) => {
+                    if cfg!(feature = "std") {
+                        abort!(
+                            span,
+                            "`#[should_reset]` is not supported with `std`, the test process can not reset the device"
+                        );
+                    }
+                    should_reset = Some(attr.within);
+                }
+                FuncAttribute::ShouldFault(attr) => {
+                    if cfg!(not(feature = "fault")) {
+                        abort!(
+                            span,
+                            "`#[should_fault]` needs the exception handlers of embedded-test, which are installed via the feature `fault-handler`"
+                        );
+                    }
+                    should_fault = Some(attr.kind.map(|kind| kind.value()));
+                }
+                FuncAttribute::Ignore(attr) => {
+                    ignore = true;
+                    ignore_reason = attr.reason;
+                }
+                FuncAttribute::IgnoreUnless(predicate) => ignore_unless = Some(predicate),
+                FuncAttribute::Timeout(t) => timeout = Some(t),
+                FuncAttribute::Retry(n) => retry = Some(n),
+                FuncAttribute::Repeat(0) => {
+                    abort!(span, "`#[repeat]` count must be at least 1")
+                }
+                FuncAttribute::Repeat(n) => repeat = Some(n),
+                FuncAttribute::TestCase(t) => {
+                    if cases.first().is_some_and(|c| c.args.len() != t.args.len()) {
+                        abort!(
+                            span,
+                            "all `#[test_case]` attributes of a test must have the same number of arguments"
+                        );
+                    }
+                    let name = match t.name {
+                        Some(name) => name.value(),
+                        None => format!("case_{}", cases.len() + 1),
+                    };
+                    if cases.iter().any(|c| c.name == name) {
+                        abort!(span, "duplicate test case name `{}`", name);
+                    }
+                    cases.push(TestCase {
+                        name,
+                        args: t.args,
+                        type_arg: None,
+                    });
+                }
+                FuncAttribute::Instantiate(types) => instantiate.extend(types),
+                FuncAttribute::Tag(t) => {
+                    for tag in t {
+                        if !tags.contains(&tag) {
+                            tags.push(tag);
+                        }
+                    }
+                }
+                FuncAttribute::Meta(m) => {
+                    for (key, value) in m {
+                        if meta.iter().any(|(k, _)| *k == key) {
+                            abort!(span, "duplicate `meta` key `{}`", key);
+                        }
+                        meta.push((key, value));
+                    }
+                }
             }
         }
 
-        if check_fn_sig(&func.sig).is_err() || func.sig.inputs.len() > 1 {
+        // Every stage but the last one has to return, so that the runner resets the device
+        if should_panic && stages.is_some() {
+            abort!(func.sig, "a multi-stage test can not be `#[should_panic]`");
+        }
+        if should_panic && should_reset.is_some() {
+            abort!(
+                func.sig,
+                "a test can not be both `#[should_panic]` and `#[should_reset]`"
+            );
+        }
+        if should_fault.is_some() && (should_panic || should_reset.is_some() || stages.is_some()) {
+            abort!(
+                func.sig,
+                "a `#[should_fault]` test can not be `#[should_panic]`, `#[should_reset]` or have multiple `stages`"
+            );
+        }
+        if should_reset.is_some() && stages.is_some() {
+            abort!(
+                func.sig,
+                "a multi-stage test can not be `#[should_reset]`. Call `embedded_test::expect_reset()` in the stage which resets the device instead"
+            );
+        }
+
+        if bench.is_some() && (should_panic || should_reset.is_some() || should_fault.is_some()) {
+            abort!(
+                func.sig,
+                "a `#[bench]` function can not be `#[should_panic]`, `#[should_reset]` or `#[should_fault]`"
+            );
+        }
+        if bench.is_some() && repeat.is_some() {
+            abort!(
+                func.sig,
+                "a `#[bench]` function can not be `#[repeat]`ed, use `#[bench(iterations = n)]` instead"
+            );
+        }
+
+        // Parameters of the test cases come after the (optional) state parameter
+        let case_args = cases.first().map(|c| c.args.len()).unwrap_or_default();
+
+        // A generic test is instantiated for every type (and every test case)
+        let mut sig = func.sig.clone();
+        if !instantiate.is_empty() {
+            let type_params = sig.generics.type_params().count();
+            if type_params != 1 || sig.generics.params.len() != 1 {
+                abort!(
+                    sig.generics,
+                    "`#[instantiate(...)]` requires a `#[test]` function with exactly one type parameter (e.g. `fn test<T: Trait>()`)",
+                );
+            }
+            sig.generics = Default::default();
+            cases = instantiate_cases(&instantiate, cases);
+            for (i, case) in cases.iter().enumerate() {
+                if cases[..i].iter().any(|c| c.name == case.name) {
+                    abort!(case.type_arg, "duplicate type `{}`", case.name);
+                }
+            }
+        } else if !sig.generics.params.is_empty() {
+            abort!(
+                sig.generics,
+                "generic `#[test]` functions must be instantiated with concrete types via `#[instantiate(Type1, Type2)]`",
+            );
+        }
+
+        let mut fixture_params = vec![];
+        let mut info_param = None;
+        let mut bencher_param = None;
+        let mut inputs = vec![];
+        for (i, arg) in func.sig.inputs.iter().enumerate() {
+            match fixture_param(arg) {
+                Some(param) if fixtures.contains(&param.name.to_string()) => {
+                    fixture_params.push((i, param))
+                }
+                _ if info_param.is_none() && is_test_info(arg) => info_param = Some(i),
+                _ if bencher_param.is_none() && is_bencher(arg) => {
+                    if bench.is_none() {
+                        abort!(arg, "only `#[bench]` functions can take a `Bencher`");
+                    }
+                    bencher_param = Some(i)
+                }
+                _ => inputs.push(arg),
+            }
+        }
+        let (spawner_param, inputs) = split_spawner_param(&func, inputs);
+
+        if bench.is_some() && bencher_param.is_none() {
+            abort!(
+                func.sig,
+                "`#[bench]` function must take a `bencher: &mut Bencher` parameter, whose closure is measured",
+            );
+        }
+
+        if check_fn_sig(&sig).is_err() || inputs.len() > case_args + 1 {
             abort!(
                 func.sig,
                 "`#[test]` function must have signature `async fn(state: Type)` (async/parameter are optional)",
             );
         }
 
+        if inputs.len() < case_args {
+            abort!(
+                func.sig,
+                "`#[test]` function must take one parameter per `#[test_case]` argument (after the optional state parameter)",
+            );
+        }
+
         if cfg!(not(feature = "embassy")) && func.sig.asyncness.is_some() {
             abort!(
                 func.sig,
@@ -88,9 +599,10 @@ impl From<FunctionWithAttributes> for TestFunc {
             );
         }
 
-        let input = if func.sig.inputs.len() == 1 {
-            Some(extract_single_value_arg(&func.sig.inputs[0]))
-            // NOTE we cannot check the argument type matches `init.state` at this point
+        let input = if inputs.len() == case_args + 1 {
+            Some(extract_arg_type(inputs[0]))
+            // NOTE we cannot check the argument type matches `init.state` at this point,
+            // neither whether a reference is allowed (only if the teardown function takes the state back)
         } else {
             None
         };
@@ -100,21 +612,77 @@ impl From<FunctionWithAttributes> for TestFunc {
             asyncness: func.sig.asyncness.is_some(),
             func,
             input,
+            fixtures: fixture_params,
+            info_param,
+            spawner_param,
+            bencher_param,
+            cases,
             should_panic,
+            expected_panic,
+            should_reset,
+            should_fault,
             ignore,
+            ignore_reason,
+            ignore_unless,
             timeout,
+            retry,
+            repeat,
             custom_init,
+            executor,
+            stages,
+            bench,
+            init_path: None,
+            tags,
+            meta,
         }
     }
 }
 
+/// Combines every type of `#[instantiate(...)]` with every `#[test_case(...)]` (if any)
+fn instantiate_cases(types: &[Type], cases: Vec<TestCase>) -> Vec<TestCase> {
+    let mut instances = vec![];
+    for ty in types {
+        let instance = format!("<{}>", type_name(ty));
+        if cases.is_empty() {
+            instances.push(TestCase {
+                name: instance.clone(),
+                args: vec![],
+                type_arg: Some(ty.clone()),
+            });
+        }
+        for case in &cases {
+            instances.push(TestCase {
+                name: format!("{}::{}", instance, case.name),
+                args: case.args.clone(),
+                type_arg: Some(ty.clone()),
+            });
+        }
+    }
+    instances
+}
+
+/// Renders a type as it would be written in Rust code (e.g. `Vec<u8>` or `&'static str`)
+fn type_name(ty: &Type) -> String {
+    let tokens = quote::quote!(#ty).to_string();
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut name = String::new();
+    let mut chars = tokens.chars().peekable();
+    while let Some(c) = chars.next() {
+        // Only keep spaces which separate two words (e.g. in `dyn Trait`)
+        if c != ' ' || (is_word(name.chars().last()) && is_word(chars.peek().copied())) {
+            name.push(c);
+        }
+    }
+    name
+}
+
 pub(crate) struct OtherFunc(pub FunctionWithAttributes);
 impl From<FunctionWithAttributes> for OtherFunc {
     fn from(func: FunctionWithAttributes) -> Self {
         if let Some((_attr, span)) = func.attributes.first() {
             abort!(
                 span,
-                "Only `#[test]` or `#[init]` functions can have such an attribute"
+                "Only `#[test]`, `#[bench]`, `#[init]`, `#[group_init]`, `#[teardown]` or `#[fixture]` functions can have such an attribute"
             );
         }
         OtherFunc(func)
@@ -123,25 +691,48 @@ impl From<FunctionWithAttributes> for OtherFunc {
 
 pub(crate) enum AnnotatedFunction {
     Init(InitFunc),
-    Test(TestFunc),
+    GroupInit(InitFunc),
+    Teardown(TeardownFunc),
+    Fixture(FixtureFunc),
+    Test(Box<TestFunc>),
     Other(OtherFunc),
 }
 
-impl From<FunctionWithAttributes> for AnnotatedFunction {
-    fn from(func: FunctionWithAttributes) -> Self {
+impl AnnotatedFunction {
+    /// Categorizes a function of the test module. `fixtures` are the names of all fixtures available to the module.
+    pub fn new(func: FunctionWithAttributes, fixtures: &[String]) -> Self {
         enum FuncKind {
             Init,
+            GroupInit,
+            Teardown,
+            Fixture,
             Test,
         }
         let mut func_kind = None;
         for (attr, span) in &func.attributes {
             match attr {
                 FuncAttribute::Init if func_kind.is_none() => func_kind = Some(FuncKind::Init),
-                FuncAttribute::Test(_) if func_kind.is_none() => func_kind = Some(FuncKind::Test),
-                FuncAttribute::Init | FuncAttribute::Test(_) => {
+                FuncAttribute::GroupInit if func_kind.is_none() => {
+                    func_kind = Some(FuncKind::GroupInit)
+                }
+                FuncAttribute::Teardown if func_kind.is_none() => {
+                    func_kind = Some(FuncKind::Teardown)
+                }
+                FuncAttribute::Fixture if func_kind.is_none() => {
+                    func_kind = Some(FuncKind::Fixture)
+                }
+                FuncAttribute::Test(_) | FuncAttribute::Bench(_) if func_kind.is_none() => {
+                    func_kind = Some(FuncKind::Test)
+                }
+                FuncAttribute::Init
+                | FuncAttribute::GroupInit
+                | FuncAttribute::Teardown
+                | FuncAttribute::Fixture
+                | FuncAttribute::Test(_)
+                | FuncAttribute::Bench(_) => {
                     abort!(
                         span,
-                        "A function can only be marked with one of `#[init]` or `#[test]`"
+                        "A function can only be marked with one of `#[init]`, `#[group_init]`, `#[teardown]`, `#[fixture]`, `#[test]` or `#[bench]`"
                     );
                 }
                 _ => {}
@@ -150,7 +741,12 @@ impl From<FunctionWithAttributes> for AnnotatedFunction {
 
         match func_kind {
             Some(FuncKind::Init) => AnnotatedFunction::Init(InitFunc::from(func)),
-            Some(FuncKind::Test) => AnnotatedFunction::Test(TestFunc::from(func)),
+            Some(FuncKind::GroupInit) => AnnotatedFunction::GroupInit(InitFunc::from(func)),
+            Some(FuncKind::Teardown) => AnnotatedFunction::Teardown(TeardownFunc::from(func)),
+            Some(FuncKind::Fixture) => AnnotatedFunction::Fixture(FixtureFunc::from(func)),
+            Some(FuncKind::Test) => {
+                AnnotatedFunction::Test(Box::new(TestFunc::new(func, fixtures)))
+            }
             None => AnnotatedFunction::Other(OtherFunc::from(func)),
         }
     }
@@ -183,6 +779,114 @@ fn check_fn_sig(sig: &syn::Signature) -> Result<(), ()> {
     }
 }
 
+/// Returns whether the parameter is an `info: &TestInfo` (or `&embedded_test::TestInfo`)
+fn is_test_info(arg: &syn::FnArg) -> bool {
+    let syn::FnArg::Typed(pat) = arg else {
+        return false;
+    };
+    let Type::Reference(reference) = &*pat.ty else {
+        return false;
+    };
+    match &*reference.elem {
+        Type::Path(path) if reference.mutability.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "TestInfo"),
+        _ => false,
+    }
+}
+
+/// Splits off an `info: &TestInfo` parameter, returns its position and the remaining parameters
+fn split_info_param(func: &ItemFn) -> (Option<usize>, Vec<&syn::FnArg>) {
+    let mut info_param = None;
+    let mut inputs = vec![];
+    for (i, arg) in func.sig.inputs.iter().enumerate() {
+        if info_param.is_none() && is_test_info(arg) {
+            info_param = Some(i);
+        } else {
+            inputs.push(arg);
+        }
+    }
+    (info_param, inputs)
+}
+
+fn is_bencher(arg: &syn::FnArg) -> bool {
+    let syn::FnArg::Typed(pat) = arg else {
+        return false;
+    };
+    let Type::Reference(reference) = &*pat.ty else {
+        return false;
+    };
+    match &*reference.elem {
+        Type::Path(path) if reference.mutability.is_some() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "Bencher"),
+        _ => false,
+    }
+}
+
+fn is_outcome(arg: &syn::FnArg) -> bool {
+    let syn::FnArg::Typed(pat) = arg else {
+        return false;
+    };
+    match &*pat.ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "Outcome"),
+        _ => false,
+    }
+}
+
+fn is_spawner(arg: &syn::FnArg) -> bool {
+    let syn::FnArg::Typed(pat) = arg else {
+        return false;
+    };
+    match &*pat.ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "Spawner"),
+        _ => false,
+    }
+}
+
+/// Splits off a `spawner: Spawner` parameter from the `inputs` of an async function,
+/// returns its position and the remaining parameters
+fn split_spawner_param<'a>(
+    func: &'a ItemFn,
+    inputs: Vec<&'a syn::FnArg>,
+) -> (Option<usize>, Vec<&'a syn::FnArg>) {
+    let Some(spawner) = inputs.iter().position(|arg| is_spawner(arg)) else {
+        return (None, inputs);
+    };
+    let arg = inputs[spawner];
+    if func.sig.asyncness.is_none() {
+        abort!(
+            arg,
+            "the `Spawner` can only be passed to async functions, which are run by the executor"
+        );
+    }
+    let position = func.sig.inputs.iter().position(|a| std::ptr::eq(a, arg));
+    let inputs = inputs
+        .into_iter()
+        .filter(|a| !std::ptr::eq(*a, arg))
+        .collect();
+    (position, inputs)
+}
+
+fn extract_arg_type(arg: &syn::FnArg) -> Type {
+    if let syn::FnArg::Typed(pat) = arg {
+        return *pat.ty.clone();
+    }
+    abort!(arg, "parameter must be a single value, not a reference");
+}
+
 fn extract_single_value_arg(arg: &syn::FnArg) -> Type {
     if let syn::FnArg::Typed(pat) = arg {
         match &*pat.ty {
@@ -1,63 +1,255 @@
 use crate::attributes::tests::parse::Module;
-use crate::attributes::tests::parse::{FunctionWithAttributes, MacroArgs};
-use crate::attributes::tests::validate::{AnnotatedFunction, InitFunc, OtherFunc, TestFunc};
+use crate::attributes::tests::parse::{FuncAttribute, FunctionWithAttributes, MacroArgs};
+use crate::attributes::tests::validate::{
+    AnnotatedFunction, FixtureFunc, FixtureParam, InitFunc, OtherFunc, TeardownFunc, TestFunc,
+};
 use proc_macro_error3::{abort, abort_call_site};
 use quote::quote;
 use std::collections::HashMap;
-use syn::Item;
+use syn::{Attribute, Item, Visibility};
 
 pub(crate) struct ValidatedModule {
     pub module_name: String,
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    /// Names of the nested modules from the `#[tests]` module down to this module (empty for the `#[tests]` module itself)
+    pub path: Vec<String>,
     pub default_init: Option<String>,
     pub init_funcs: HashMap<String, InitFunc>,
+    /// `#[group_init]` function, which is called once for all tests of the module (in a single boot)
+    pub group_init: Option<InitFunc>,
+    pub teardown: Option<TeardownFunc>,
+    /// Own and inherited `#[fixture]` functions
+    pub fixtures: Vec<FixtureFunc>,
     pub tests: Vec<TestFunc>,
     pub untouched_tokens: Vec<Item>,
+    pub submodules: Vec<ValidatedModule>,
     pub macro_args: MacroArgs,
 }
 
 impl ValidatedModule {
     pub(crate) fn from_module_and_args(module: Module, macro_args: MacroArgs) -> Self {
+        Self::from_module(module, macro_args, None)
+    }
+
+    /// Validates a (possibly nested) module. Nested modules inherit the `#[init]` and `#[teardown]` function
+    /// of their parent, unless they define their own. Fixtures are inherited, unless a fixture of the same name is defined.
+    fn from_module(
+        module: Module,
+        macro_args: MacroArgs,
+        parent: Option<&ValidatedModule>,
+    ) -> Self {
         let Module {
             name: module_name,
+            attrs,
+            vis,
             functions,
+            submodules,
             mut untouched_tokens,
         } = module;
 
-        let (default_init_func, tests, other_funcs) = categorize_functions(functions);
+        // The names of the fixtures are needed upfront, to tell fixture parameters of a test apart from the others
+        let mut fixture_names: Vec<String> = functions
+            .iter()
+            .filter(|f| {
+                f.attributes
+                    .iter()
+                    .any(|(attr, _)| matches!(attr, FuncAttribute::Fixture))
+            })
+            .map(|f| f.func.sig.ident.to_string())
+            .collect();
+        if let Some(parent) = parent {
+            fixture_names.extend(parent.fixtures.iter().map(|f| f.name.clone()));
+        }
+
+        let CategorizedFunctions {
+            init_func: mut default_init_func,
+            group_init,
+            teardown_func: mut teardown,
+            mut fixtures,
+            tests,
+            other_funcs,
+        } = categorize_functions(functions, &fixture_names);
+        if let Some(parent) = parent {
+            for fixture in &parent.fixtures {
+                if !fixtures.iter().any(|f| f.name == fixture.name) {
+                    fixtures.push(fixture.inherited());
+                }
+            }
+        }
+        // A group doesn't use the init/teardown function of the parent module
+        if let Some(parent) = parent.filter(|_| group_init.is_none()) {
+            if default_init_func.is_none() {
+                default_init_func = parent.default_init().map(InitFunc::inherited);
+            }
+            if teardown.is_none() {
+                teardown = parent.teardown.as_ref().map(TeardownFunc::inherited);
+            }
+        }
         let default_init = default_init_func.as_ref().map(|i| i.name.clone());
+        if let Some(group_init) = &group_init {
+            validate_group(
+                group_init,
+                default_init_func.as_ref(),
+                teardown.as_ref(),
+                &tests,
+            );
+        }
 
         let (init_funcs, other_funcs) =
             collect_init_functions(&tests, default_init_func, other_funcs);
 
         untouched_tokens.extend(other_funcs.into_iter().map(|f| Item::Fn(f.0.func))); // Add unused functions back to untouched tokens
 
-        let m = ValidatedModule {
+        let mut path = vec![];
+        if let Some(parent) = parent {
+            path.extend(parent.path.iter().cloned());
+            path.push(module_name.clone());
+        }
+
+        let mut m = ValidatedModule {
             module_name,
+            attrs,
+            vis,
+            path,
             init_funcs,
+            group_init,
+            teardown,
+            fixtures,
             tests,
             untouched_tokens,
+            submodules: vec![],
             macro_args,
             default_init,
         };
+        m.validate_fixtures();
         m.validate_functions();
         m.validate_macro_args();
+
+        m.submodules = submodules
+            .into_iter()
+            .map(|submodule| Self::from_module(submodule, m.macro_args.clone(), Some(&m)))
+            .collect();
         m
     }
 
-    pub(crate) fn init_function_for_test(&self, test: &TestFunc) -> Option<&InitFunc> {
-        if let Some(custom_init) = test.custom_init.as_ref() {
-            return Some(self.init_funcs.get(&custom_init.to_string()).unwrap());
+    /// The context of a freestanding `#[embedded_test::test]` function: No init/teardown functions and default arguments
+    pub(crate) fn freestanding() -> Self {
+        ValidatedModule {
+            module_name: String::new(),
+            attrs: vec![],
+            vis: Visibility::Inherited,
+            path: vec![],
+            default_init: None,
+            init_funcs: HashMap::new(),
+            group_init: None,
+            teardown: None,
+            fixtures: vec![],
+            tests: vec![],
+            untouched_tokens: vec![],
+            submodules: vec![],
+            macro_args: MacroArgs::default(),
         }
+    }
 
+    fn default_init(&self) -> Option<&InitFunc> {
         self.default_init
             .as_ref()
             .map(|i| self.init_funcs.get(i.as_str()).unwrap())
     }
 
+    /// Whether the test or any function called for it (init, teardown, fixtures) is async
+    pub(crate) fn test_is_async(&self, test: &TestFunc) -> bool {
+        test.asyncness
+            || self
+                .init_function_for_test(test)
+                .is_some_and(|init| init.asyncness)
+            || self.teardown.iter().any(|teardown| teardown.asyncness)
+            || self
+                .fixtures_for_test(test)
+                .iter()
+                .any(|fixture| fixture.asyncness)
+    }
+
+    pub(crate) fn init_function_for_test(&self, test: &TestFunc) -> Option<&InitFunc> {
+        if let Some(custom_init) = test.custom_init.as_ref() {
+            return Some(self.init_funcs.get(&custom_init.to_string()).unwrap());
+        }
+
+        self.default_init()
+    }
+
+    /// Returns the fixtures needed by the test, in the order in which they have to be built
+    pub(crate) fn fixtures_for_test(&self, test: &TestFunc) -> Vec<&FixtureFunc> {
+        let mut order = vec![];
+        for (_, param) in &test.fixtures {
+            self.visit_fixture(param, &mut vec![], &mut order);
+        }
+        order
+    }
+
+    /// Depth-first search through the dependencies of the fixture requested by `param`.
+    /// `stack` holds the fixtures which are currently being visited, to detect cycles.
+    fn visit_fixture<'a>(
+        &'a self,
+        param: &FixtureParam,
+        stack: &mut Vec<String>,
+        order: &mut Vec<&'a FixtureFunc>,
+    ) {
+        let name = param.name.to_string();
+        let Some(fixture) = self.fixtures.iter().find(|f| f.name == name) else {
+            abort!(
+                param.name,
+                "unknown fixture `{}`. The parameters of a `#[fixture]` function must be named after other fixtures",
+                name
+            );
+        };
+
+        if let Some(pos) = stack.iter().position(|n| *n == name) {
+            let cycle: Vec<String> = stack[pos..]
+                .iter()
+                .chain([&name])
+                .map(|n| format!("`{}`", n))
+                .collect();
+            abort!(param.name, "cycle in fixtures: {}", cycle.join(" -> "));
+        }
+        if order.iter().any(|f| f.name == name) {
+            return;
+        }
+
+        stack.push(name);
+        for dep in &fixture.deps {
+            self.visit_fixture(dep, stack, order);
+        }
+        stack.pop();
+        order.push(fixture);
+    }
+
+    fn validate_fixtures(&self) {
+        // Unknown fixtures, mismatching types and cycles are reported at the parameter which causes them
+        for fixture in &self.fixtures {
+            let mut stack = vec![fixture.name.clone()];
+            for dep in &fixture.deps {
+                self.visit_fixture(dep, &mut stack, &mut vec![]);
+            }
+        }
+        for test in &self.tests {
+            self.fixtures_for_test(test);
+        }
+    }
+
     fn validate_functions(&self) {
         // Validate the argument type of the test function, now that the init function is parsed
         for test in &self.tests {
-            validate_argument_type(test, self.init_function_for_test(test));
+            if let Some(group_init) = &self.group_init {
+                validate_group_argument_type(test, group_init);
+                continue;
+            }
+            let init_func = self.init_function_for_test(test);
+            validate_argument_type(test, init_func, self.teardown.as_ref());
+            if let Some(teardown) = &self.teardown {
+                validate_teardown_state(test, init_func, teardown);
+            }
         }
     }
 
@@ -71,28 +263,60 @@ impl ValidatedModule {
             );
         }
 
-        // Validate a custom executor is provided if needed and at least one test/init is async
+        // The same applies to the executor of a single test
+        if cfg!(not(all(feature = "embassy", feature = "external-executor"))) {
+            if let Some(executor) = self.tests.iter().find_map(|test| test.executor.as_ref()) {
+                abort!(
+                    executor,
+                    "`#[test]` attribute doesn't take an executor unless the features `embassy` and `external-executor` are enabled",
+                );
+            }
+        }
+
+        // Validate a custom executor is provided if needed and at least one test/init is async.
+        // Tests which have their own executor run their init/teardown function and fixtures on it as well.
         if cfg!(feature = "external-executor")
             && self.macro_args.executor.is_none()
-            && (self.tests.iter().any(|test| test.asyncness)
-                || self.init_funcs.iter().any(|(_, init)| init.asyncness))
+            && (self.group_init.iter().any(|init| init.asyncness)
+                || self
+                    .tests
+                    .iter()
+                    .any(|test| test.executor.is_none() && self.test_is_async(test)))
         {
             abort_call_site!(
                 "async test/init func requires that an executor is provided via `#[embedded_test::tests(executor = ...)]` because the feature `external-executor` is enabled",
             );
         }
+
+        if self.macro_args.default_repeat == Some(0) {
+            abort_call_site!("`default_repeat` must be at least 1");
+        }
     }
 }
 
+/// The functions of a module, sorted by their kind
+struct CategorizedFunctions {
+    init_func: Option<InitFunc>,
+    group_init: Option<InitFunc>,
+    teardown_func: Option<TeardownFunc>,
+    fixtures: Vec<FixtureFunc>,
+    tests: Vec<TestFunc>,
+    other_funcs: Vec<OtherFunc>,
+}
+
 fn categorize_functions(
     functions: Vec<FunctionWithAttributes>,
-) -> (Option<InitFunc>, Vec<TestFunc>, Vec<OtherFunc>) {
+    fixture_names: &[String],
+) -> CategorizedFunctions {
     let mut init_func = None;
+    let mut group_init = None;
+    let mut teardown_func = None;
+    let mut fixtures = vec![];
     let mut tests = vec![];
     let mut other_funcs = vec![];
 
     for func in functions {
-        match AnnotatedFunction::from(func) {
+        match AnnotatedFunction::new(func, fixture_names) {
             AnnotatedFunction::Init(i) if init_func.is_none() => init_func = Some(i),
             AnnotatedFunction::Init(i) => {
                 abort!(
@@ -100,12 +324,34 @@ fn categorize_functions(
                     "only one `#[init]` function is allowed in a test module",
                 );
             }
-            AnnotatedFunction::Test(t) => tests.push(t),
+            AnnotatedFunction::GroupInit(i) if group_init.is_none() => group_init = Some(i),
+            AnnotatedFunction::GroupInit(i) => {
+                abort!(
+                    i.func.sig,
+                    "only one `#[group_init]` function is allowed in a test module",
+                );
+            }
+            AnnotatedFunction::Teardown(t) if teardown_func.is_none() => teardown_func = Some(t),
+            AnnotatedFunction::Teardown(t) => {
+                abort!(
+                    t.func.sig,
+                    "only one `#[teardown]` function is allowed in a test module",
+                );
+            }
+            AnnotatedFunction::Fixture(f) => fixtures.push(f),
+            AnnotatedFunction::Test(t) => tests.push(*t),
             AnnotatedFunction::Other(f) => other_funcs.push(f),
         }
     }
 
-    (init_func, tests, other_funcs)
+    CategorizedFunctions {
+        init_func,
+        group_init,
+        teardown_func,
+        fixtures,
+        tests,
+        other_funcs,
+    }
 }
 
 fn collect_init_functions(
@@ -145,10 +391,28 @@ fn collect_init_functions(
     (map, other_funcs)
 }
 
-fn validate_argument_type(test: &TestFunc, init_func: Option<&InitFunc>) {
+fn validate_argument_type(
+    test: &TestFunc,
+    init_func: Option<&InitFunc>,
+    teardown: Option<&TeardownFunc>,
+) {
     let init_func = init_func.map(|i| (i.name.as_str(), i.state.as_ref()));
 
-    match (&test.input, init_func) {
+    // A test can only borrow the state, if the teardown function takes it back afterward
+    let input = match &test.input {
+        Some(syn::Type::Reference(reference)) => {
+            if teardown.and_then(|t| t.state.as_ref()).is_none() {
+                abort!(
+                    reference,
+                    "parameter must be a single value, not a reference"
+                );
+            }
+            Some(&*reference.elem)
+        }
+        input => input.as_ref(),
+    };
+
+    match (input, init_func) {
         (Some(_), None) => {
             abort!(
                 test.func.sig,
@@ -162,18 +426,143 @@ fn validate_argument_type(test: &TestFunc, init_func: Option<&InitFunc>) {
                 init_fn_name
             );
         }
-        (Some(actual_type), Some((init_fn_name, Some(expected_type))))
-            if actual_type != expected_type =>
-        {
+        // The type itself is checked at compile time, see `call_test_fn::state_arg` and `export::InitState`
+        _ => {}
+    }
+}
+
+/// The tests of a `#[group_init]` module run one after another in a single boot, so they can neither have
+/// their own init/teardown function nor end the boot by an expected panic.
+fn validate_group(
+    group_init: &InitFunc,
+    init: Option<&InitFunc>,
+    teardown: Option<&TeardownFunc>,
+    tests: &[TestFunc],
+) {
+    if let Some(init) = init {
+        abort!(
+            init.func.sig,
+            "a test module can not have both an `#[init]` and a `#[group_init]` function",
+        );
+    }
+    if let Some(teardown) = teardown {
+        abort!(
+            teardown.func.sig,
+            "a test module with a `#[group_init]` function can not have a `#[teardown]` function",
+        );
+    }
+    for test in tests {
+        if let Some(custom_init) = &test.custom_init {
             abort!(
-                actual_type,
-                "this type must match the return type `{}` of the init function `{}`",
-                type_ident(expected_type),
-                init_fn_name
+                custom_init,
+                "the tests of a module with a `#[group_init]` function can not have a custom init function",
+            );
+        }
+        if test.should_panic {
+            abort!(
+                test.func.sig,
+                "the tests of a module with a `#[group_init]` function can not be `#[should_panic]`, since a panic ends the group `{}`",
+                group_init.name
+            );
+        }
+        if let Some(executor) = &test.executor {
+            abort!(
+                executor,
+                "the tests of a module with a `#[group_init]` function are run by the executor of the group `{}`, they can not have their own executor",
+                group_init.name
+            );
+        }
+        if test.should_fault.is_some() {
+            abort!(
+                test.func.sig,
+                "the tests of a module with a `#[group_init]` function can not be `#[should_fault]`, since a fault ends the group `{}`",
+                group_init.name
+            );
+        }
+        if test.should_reset.is_some() {
+            abort!(
+                test.func.sig,
+                "the tests of a module with a `#[group_init]` function can not be `#[should_reset]`, since a reset ends the group `{}`",
+                group_init.name
+            );
+        }
+        if test.stages.is_some() {
+            abort!(
+                test.func.sig,
+                "the tests of a module with a `#[group_init]` function are run without a reset in between, they can not have multiple `stages`",
+            );
+        }
+        if test.retry.is_some() || test.repeat.is_some() {
+            abort!(
+                test.func.sig,
+                "the tests of a module with a `#[group_init]` function are run once, one after another. They can not be `#[retry]`ed or `#[repeat]`ed",
+            );
+        }
+        if test.bench.is_some() {
+            abort!(
+                test.func.sig,
+                "a module with a `#[group_init]` function can not contain `#[bench]` functions, since a benchmark ends the group `{}` with its report",
+                group_init.name
+            );
+        }
+    }
+}
+
+/// The state of a `#[group_init]` function is shared by all tests of the group, so they can only borrow it
+fn validate_group_argument_type(test: &TestFunc, group_init: &InitFunc) {
+    let Some(input) = &test.input else {
+        return;
+    };
+    if !matches!(input, syn::Type::Reference(_)) {
+        abort!(
+            input,
+            "the state of the `#[group_init]` function `{}` is shared by all tests of the module. Borrow it instead (e.g. `&mut {}`)",
+            group_init.name,
+            type_ident(input)
+        );
+    }
+    if group_init.state.is_none() {
+        abort!(
+            test.func.sig,
+            "this test function has an argument but the `#[group_init]` function `{}` does not return a state",
+            group_init.name
+        );
+    }
+}
+
+fn validate_teardown_state(test: &TestFunc, init_func: Option<&InitFunc>, teardown: &TeardownFunc) {
+    let Some(teardown_state) = &teardown.state else {
+        return;
+    };
+
+    match init_func.map(|i| (i.name.as_str(), i.state.as_ref())) {
+        None => {
+            abort!(
+                teardown_state,
+                "the `#[teardown]` function takes a state, but the test `{}` has no init function",
+                test.func.sig.ident
+            );
+        }
+        Some((init_fn_name, None)) => {
+            abort!(
+                teardown_state,
+                "the `#[teardown]` function takes a state, but the init function `{}` of test `{}` does not return a state",
+                init_fn_name,
+                test.func.sig.ident
             );
         }
         _ => {}
     }
+
+    if let Some(input) = &test.input {
+        if !matches!(input, syn::Type::Reference(_)) {
+            abort!(
+                input,
+                "the state is moved into the test, but the `#[teardown]` function takes it back afterwards. Borrow the state instead (e.g. `&mut {}`)",
+                type_ident(input)
+            );
+        }
+    }
 }
 
 fn type_ident(ty: &syn::Type) -> String {
@@ -6,8 +6,9 @@ use proc_macro_error3::proc_macro_error;
 /// Attribute to be placed on the test suite's module.
 ///
 /// ## Arguments
-/// - `default-timeout`: The default timeout in seconds for all tests in the suite. This can be overridden on a per-test basis. If not specified here or on a per-test basis, the default timeout is 60 seconds.
+/// - `default-timeout`: The default timeout for all tests in the suite, in seconds (`10`) or with a unit (`250ms`, `"2min"`, `default_timeout(millis = 250)`). This can be overridden on a per-test basis. If not specified here or on a per-test basis, the default timeout is 60 seconds.
 /// - `executor`: The custom executor to use for running async tests. This is only required if the features `embassy` and `external-executor` are enabled.
+///   A single test can be run on another executor via `#[test(executor = ...)]` (e.g. an executor on the second core).
 ///
 /// ## Examples
 ///
@@ -31,13 +32,24 @@ use proc_macro_error3::proc_macro_error;
 /// Define a test suite and customize everything:
 ///
 /// ```rust,no_run
-/// #[embedded_test::tests(default_timeout = 10, executor = embassy::executor::Executor::new())]
+/// #[embedded_test::tests(default_timeout = 10, tags("hil"), executor = embassy::executor::Executor::new())]
 /// mod tests {
 ///     #[init]
 ///     fn init() {
 ///         // Initialize the hardware
 ///     }
 ///
+///     #[teardown]
+///     fn teardown(outcome: embedded_test::Outcome) {
+///         // Release the hardware, runs after every test
+///     }
+///
+///     #[fixture]
+///     fn buffer() -> [u8; 16] {
+///         // Built for every test which takes a `buffer` parameter
+///         [0; 16]
+///     }
+///
 ///     #[test]
 ///     fn test() {
 ///         log::info("Start....")
@@ -45,8 +57,10 @@ use proc_macro_error3::proc_macro_error;
 ///     }
 ///
 ///     #[test]
-///     #[timeout(5)]
-///     fn test2() {
+///     #[timeout(500ms)]
+///     #[tag("slow")]
+///     #[retry(2)]
+///     fn test2(buffer: [u8; 16]) {
 ///        // Test the hardware
 ///     }
 /// }
@@ -57,6 +71,94 @@ pub fn tests(args: TokenStream, input: TokenStream) -> TokenStream {
     attributes::tests::expand(args, input)
 }
 
+/// Attribute to be placed on a freestanding test function, outside of a `#[embedded_test::tests]` module
+///
+/// This allows to spread tests across multiple files/modules, or to generate tests via `macro_rules!`.
+/// All attributes of a test in a `#[embedded_test::tests]` module (e.g. `#[tag]` or `#[test_case]`) are supported as well.
+///
+/// ## Arguments
+/// - `init`: Path to a (non-async) function, which returns the state passed to the test.
+/// - `timeout`: The timeout of the test, see `#[timeout]`.
+/// - `ignore` or `ignore = "reason"`: Ignores the test.
+/// - `should_panic` or `should_panic(expected = "message")`: The test is expected to panic.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// fn init() -> u32 {
+///     42
+/// }
+///
+/// #[embedded_test::test(init = init, timeout = 500ms)]
+/// fn test(state: u32) {
+///     assert_eq!(state, 42);
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn test(args: TokenStream, input: TokenStream) -> TokenStream {
+    attributes::test::expand(args, input)
+}
+
+/// Attribute to be placed on a module, which defines a reusable test suite
+///
+/// The tests of the suite are generic over a type parameter (e.g. a fixture, which provides the driver under test).
+/// The suite is instantiated for a concrete type via `embedded_test::instantiate_suite!`, which generates a
+/// `#[embedded_test::tests]` module. Suites which are `pub` can be instantiated in other crates.
+///
+/// Paths in the suite are resolved where the suite is instantiated (e.g. `use super::*`),
+/// except for paths starting with `crate::`, which refer to the crate defining the suite.
+///
+/// ## Arguments
+/// - The type parameter, optionally with bounds (e.g. `F: FlashFixture`)
+/// - Optionally followed by the arguments of `#[embedded_test::tests]` (e.g. `default_timeout = 10`)
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// pub trait FlashFixture {
+///     type Flash: embedded_storage::nor_flash::NorFlash;
+///     fn init() -> Self::Flash;
+/// }
+///
+/// #[embedded_test::suite(F: crate::FlashFixture)]
+/// pub mod spi_flash_suite {
+///     use crate::FlashFixture;
+///
+///     #[init]
+///     fn init() -> <F as FlashFixture>::Flash {
+///         F::init()
+///     }
+///
+///     #[test]
+///     fn erase(flash: <F as FlashFixture>::Flash) {
+///         // ...
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn suite(args: TokenStream, input: TokenStream) -> TokenStream {
+    attributes::suite::expand(args, input)
+}
+
+/// Instantiates a test suite defined via `#[embedded_test::suite]` for a concrete type
+///
+/// The generated test module is named after the type (e.g. `w25q32_fixture` for `W25Q32Fixture`),
+/// unless a name is given explicitly. The tests are named accordingly, e.g. `tests::w25q32_fixture::erase`.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// embedded_test::instantiate_suite!(my_drivers::spi_flash_suite, W25Q32Fixture);
+/// embedded_test::instantiate_suite!(my_drivers::spi_flash_suite, Mx25Fixture as mx25);
+/// ```
+#[proc_macro]
+#[proc_macro_error]
+pub fn instantiate_suite(input: TokenStream) -> TokenStream {
+    attributes::suite::expand_instantiate(input)
+}
+
 /// Attribute to be placed on a global setup function for the test suite
 ///
 /// Use this function to set up a global logger
@@ -0,0 +1,254 @@
+//! Benchmarks (`#[bench]`), which measure the cycles of a closure on the target
+
+/// Maximum number of measured iterations. The cycles of every iteration are kept, to compute the median.
+const MAX_ITERATIONS: usize = 256;
+
+/// Measures the cycles of a closure, passed to the `bencher: &mut Bencher` parameter of a `#[bench]` function.
+///
+/// The closure is run for the warm-up first, then once per measured iteration. The minimum, median, mean and
+/// maximum cycles of the iterations are reported to the runner when the benchmark returns (`"status":"bench"` via
+/// semihosting stderr). Benchmarks are exported with `"kind":"bench"`, so that the runner only runs them when asked to.
+///
+/// ```rust,ignore
+/// #[bench(iterations = 100, warmup = 10)]
+/// fn crc32(bencher: &mut Bencher) {
+///     let data = [0u8; 64];
+///     bencher.iter(|| crc32(&data));
+/// }
+/// ```
+///
+/// The cycles are read from the DWT cycle counter on Cortex-M (ARMv7-M and higher), from `mcycle` on RISC-V
+/// (from the performance counter `mpccr` on the ESP32-C/H chips with esp-hal) and from `CCOUNT` on Xtensa. On `std`, the elapsed nanoseconds are measured instead.
+/// A `#[bench]` function fails to compile on targets without a cycle counter (ARMv6-M and ARMv8-M Baseline).
+pub struct Bencher {
+    warmup: u32,
+    iterations: u32,
+    samples: [u32; MAX_ITERATIONS],
+    measured: bool,
+}
+
+/// Statistics of the measured iterations, in cycles (or nanoseconds on `std`)
+pub(crate) struct Statistics {
+    pub iterations: u32,
+    pub min: u32,
+    pub median: u32,
+    pub mean: u32,
+    pub max: u32,
+}
+
+impl Bencher {
+    pub(crate) fn new(warmup: u32, iterations: u32) -> Self {
+        Self {
+            warmup,
+            iterations: iterations.min(MAX_ITERATIONS as u32),
+            samples: [0; MAX_ITERATIONS],
+            measured: false,
+        }
+    }
+
+    /// Runs `f` for the warm-up, then measures the cycles of every further run.
+    /// The result of `f` is passed through [`core::hint::black_box`], so that the computation is not optimized away.
+    pub fn iter<R>(&mut self, mut f: impl FnMut() -> R) {
+        cycle_counter::enable();
+        for _ in 0..self.warmup {
+            core::hint::black_box(f());
+        }
+        for sample in &mut self.samples[..self.iterations as usize] {
+            let start = cycle_counter::read();
+            core::hint::black_box(f());
+            *sample = cycle_counter::since(start);
+        }
+        self.measured = true;
+    }
+
+    /// Returns `None` if [`Bencher::iter`] was never called
+    pub(crate) fn statistics(&mut self) -> Option<Statistics> {
+        if !self.measured {
+            return None;
+        }
+        let samples = &mut self.samples[..self.iterations as usize];
+        samples.sort_unstable();
+        let n = samples.len();
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            ((u64::from(samples[n / 2 - 1]) + u64::from(samples[n / 2])) / 2) as u32
+        };
+        let sum: u64 = samples.iter().map(|&cycles| u64::from(cycles)).sum();
+        Some(Statistics {
+            iterations: self.iterations,
+            min: samples[0],
+            median,
+            mean: (sum / n as u64) as u32,
+            max: samples[n - 1],
+        })
+    }
+}
+
+/// Unit of the reported statistics
+#[cfg(not(feature = "std"))]
+pub(crate) const UNIT: &str = "cycles";
+#[cfg(feature = "std")]
+pub(crate) const UNIT: &str = "ns";
+
+#[cfg(feature = "std")]
+mod cycle_counter {
+    use std::time::Instant;
+
+    pub fn enable() {}
+
+    pub fn read() -> Instant {
+        Instant::now()
+    }
+
+    /// Nanoseconds since `start`, saturating (after more than 4 seconds)
+    pub fn since(start: Instant) -> u32 {
+        u32::try_from(start.elapsed().as_nanos()).unwrap_or(u32::MAX)
+    }
+}
+
+#[cfg(all(not(feature = "std"), cortex_m, not(armv6m)))]
+mod cycle_counter {
+    /// Debug Exception and Monitor Control Register
+    const DEMCR: *mut u32 = 0xE000_EDFC as *mut u32;
+    /// DWT Control Register
+    const DWT_CTRL: *mut u32 = 0xE000_1000 as *mut u32;
+    /// DWT Cycle Count Register
+    const DWT_CYCCNT: *const u32 = 0xE000_1004 as *const u32;
+    /// DWT Lock Access Register, the DWT is locked after reset on some Cortex-M7
+    const DWT_LAR: *mut u32 = 0xE000_1FB0 as *mut u32;
+
+    pub fn enable() {
+        // SAFETY: The registers of the System Control Space exist on every ARMv7-M/ARMv8-M Mainline core
+        unsafe {
+            DEMCR.write_volatile(DEMCR.read_volatile() | 1 << 24); // TRCENA
+            DWT_LAR.write_volatile(0xC5AC_CE55);
+            DWT_CTRL.write_volatile(DWT_CTRL.read_volatile() | 1); // CYCCNTENA
+        }
+    }
+
+    pub fn read() -> u32 {
+        // SAFETY: See `enable`
+        unsafe { DWT_CYCCNT.read_volatile() }
+    }
+
+    /// Cycles since `start`, the 32-bit counter wraps around
+    pub fn since(start: u32) -> u32 {
+        read().wrapping_sub(start)
+    }
+}
+
+#[cfg(all(
+    not(feature = "std"),
+    any(target_arch = "riscv32", target_arch = "riscv64")
+))]
+mod cycle_counter {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    /// Set if the ESP32-C/H performance counter (`mpccr`) has to be read instead of `mcycle`
+    static ESP_COUNTER: AtomicBool = AtomicBool::new(false);
+
+    pub fn enable() {
+        // The ESP32-C/H chips don't implement `mcycle`. They are detected via esp-hal (see `embedded-test.x`).
+        extern "C" {
+            static embedded_test_esp_cycle_counter: u8;
+        }
+        let esp = core::hint::black_box(core::ptr::addr_of!(embedded_test_esp_cycle_counter))
+            as usize
+            != 0;
+        ESP_COUNTER.store(esp, Ordering::Relaxed);
+        if esp {
+            // SAFETY: Tests run in machine mode. Counts the cycles (`mpcer`) and starts the counter (`mpcmr`).
+            unsafe {
+                core::arch::asm!("csrw 0x7e0, {}", in(reg) 1);
+                core::arch::asm!("csrw 0x7e1, {}", in(reg) 1);
+            }
+        }
+    }
+
+    pub fn read() -> u32 {
+        let cycles: usize;
+        if ESP_COUNTER.load(Ordering::Relaxed) {
+            // SAFETY: See `enable`
+            unsafe { core::arch::asm!("csrr {}, 0x7e2", out(reg) cycles) };
+        } else {
+            // SAFETY: Tests run in machine mode, where the standard `mcycle` is accessible on the other chips
+            unsafe { core::arch::asm!("csrr {}, mcycle", out(reg) cycles) };
+        }
+        cycles as u32
+    }
+
+    /// Cycles since `start`, the 32-bit counter wraps around
+    pub fn since(start: u32) -> u32 {
+        read().wrapping_sub(start)
+    }
+}
+
+#[cfg(all(not(feature = "std"), target_arch = "xtensa"))]
+mod cycle_counter {
+    pub fn enable() {}
+
+    pub fn read() -> u32 {
+        let cycles: u32;
+        // SAFETY: Reading the cycle counter has no side effects
+        unsafe { core::arch::asm!("rsr.ccount {}", out(reg) cycles) };
+        cycles
+    }
+
+    /// Cycles since `start`, the 32-bit counter wraps around
+    pub fn since(start: u32) -> u32 {
+        read().wrapping_sub(start)
+    }
+}
+
+#[cfg(not(any(
+    feature = "std",
+    all(cortex_m, not(armv6m)),
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "xtensa"
+)))]
+mod cycle_counter {
+    // Never called, a `#[bench]` function fails to compile (see `__check_bench_supported`)
+    pub fn enable() {}
+
+    pub fn read() -> u32 {
+        0
+    }
+
+    pub fn since(_start: u32) -> u32 {
+        0
+    }
+}
+
+/// Called by every `#[bench]` function. Targets without a cycle counter (ARMv6-M and ARMv8-M Baseline) can't run
+/// benchmarks, so that the build fails instead of the benchmark.
+#[cfg(not(any(
+    feature = "std",
+    all(cortex_m, not(armv6m)),
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "xtensa"
+)))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_bench_supported {
+    () => {
+        compile_error!(
+            "`#[bench]` is not supported on this target, it has no cycle counter (e.g. ARMv6-M)"
+        )
+    };
+}
+
+#[cfg(any(
+    feature = "std",
+    all(cortex_m, not(armv6m)),
+    target_arch = "riscv32",
+    target_arch = "riscv64",
+    target_arch = "xtensa"
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_bench_supported {
+    () => {};
+}
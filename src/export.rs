@@ -1,4 +1,5 @@
-use crate::{export, TestOutcome};
+use crate::{export, Bencher, Outcome, Retained, TestInfo, TestOutcome};
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicUsize, Ordering};
 
 #[cfg_attr(feature = "std", path = "std.rs")]
 #[cfg_attr(feature = "semihosting", path = "semihosting.rs")]
@@ -10,14 +11,55 @@ use embassy_executor_010 as embassy_executor;
 use embassy_executor_09 as embassy_executor;
 
 // Reexport the embassy stuff
-#[cfg(all(feature = "_embassy", not(feature = "_ariel")))]
-pub use embassy_executor::task;
 #[cfg(all(
     feature = "_embassy",
     not(feature = "external-executor"),
     not(feature = "_ariel")
 ))]
-pub use embassy_executor::Executor; // Please activate the `executor-thread` or `executor-interrupt` feature on the embassy-executor crate (v0.9.x/0.10.x)!
+pub use embassy_executor::Executor;
+#[cfg(all(feature = "_embassy", not(feature = "_ariel")))]
+pub use embassy_executor::{task, Spawner}; // Please activate the `executor-thread` or `executor-interrupt` feature on the embassy-executor crate (v0.9.x/0.10.x)!
+
+/// Exit code which signals to the runner that the test was skipped at runtime.
+/// Exit code `0` signals success, an abort signals a failure.
+/// A runner which doesn't know the exit codes below reports the test as failed, so the protocol version is unchanged.
+pub const EXIT_CODE_SKIPPED: i32 = 2;
+
+/// Exit code which signals to the runner that the init function failed, i.e. the test could not be run.
+pub const EXIT_CODE_INIT_FAILED: i32 = 3;
+
+/// Exit code which signals to the runner that a stage of a multi-stage test passed and that the device has to be reset,
+/// to run the next stage.
+pub const EXIT_CODE_EXPECT_RESET: i32 = 4;
+
+pub fn skip(reason: &str) -> ! {
+    clear_expected_reset();
+    info!("Test skipped: {}", reason);
+    hosting::report(format_args!(
+        r#"{{"status":"skipped","reason":"{}"}}"#,
+        JsonEscaped(reason)
+    ));
+    hosting::exit(EXIT_CODE_SKIPPED);
+}
+
+/// Formats a string with JSON escaping applied (without the surrounding quotes)
+pub struct JsonEscaped<'a>(pub &'a str);
+
+impl core::fmt::Display for JsonEscaped<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use core::fmt::Write;
+        for c in self.0.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '"' => f.write_str("\\\"")?,
+                '\n' => f.write_str("\\n")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
 
 pub fn check_outcome<T: TestOutcome>(outcome: T) -> ! {
     if outcome.is_success() {
@@ -29,6 +71,514 @@ pub fn check_outcome<T: TestOutcome>(outcome: T) -> ! {
     }
 }
 
+pub fn check_outcome_expecting_panic<T: TestOutcome>(outcome: T, expected: &str) -> ! {
+    let _ = outcome;
+    error!(
+        "Test exited without panicking, but a panic with message containing `{}` was expected",
+        expected
+    );
+    hosting::abort();
+}
+
+#[cfg(feature = "fault-handler")]
+pub use crate::fault::expect_fault;
+
+pub fn check_outcome_expecting_fault<T: TestOutcome>(outcome: T) -> ! {
+    let _ = outcome;
+    error!("Test exited without faulting, but a fault was expected");
+    hosting::abort();
+}
+
+/// Checks whether the panic message contains the substring given via `#[should_panic(expected = "...")]`
+/// and exits successfully if that's the case.
+pub fn check_panic_message(message: impl core::fmt::Display, expected: &str) -> ! {
+    use core::fmt::Write;
+    let mut buffer = MessageBuffer::new();
+    let _ = write!(buffer, "{}", message);
+
+    if buffer.as_str().contains(expected) {
+        info!("Test panicked with the expected message");
+        hosting::exit(0);
+    } else {
+        error!(
+            "Panic message did not match. Panic message: `{}`, expected substring: `{}`",
+            buffer.as_str(),
+            expected
+        );
+        hosting::abort();
+    }
+}
+
+/// Called by every `#[should_panic(expected = "...")]` test. Such a test is exported with `should_panic: false`,
+/// the panic handler of embedded-test (or the panic hook on `std`) checks the message and exits successfully on a match.
+/// Without it, the test would always fail, so that the build fails instead.
+#[cfg(not(any(
+    feature = "std",
+    all(feature = "panic-handler", not(feature = "_ariel"))
+)))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_expected_panic_supported {
+    () => {
+        compile_error!(
+            "`#[should_panic(expected = \"...\")]` needs the panic handler of embedded-test (feature `panic-handler`, not available with Ariel OS), which checks the panic message. Use `#[should_panic]` without `expected` instead"
+        )
+    };
+}
+
+#[cfg(any(
+    feature = "std",
+    all(feature = "panic-handler", not(feature = "_ariel"))
+))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_expected_panic_supported {
+    () => {};
+}
+
+pub use crate::__check_expected_panic_supported as check_expected_panic_supported;
+
+/// Fixed size buffer to format a panic message into, without requiring an allocator.
+/// Longer messages are truncated.
+struct MessageBuffer {
+    buf: [u8; 256],
+    len: usize,
+}
+
+impl MessageBuffer {
+    fn new() -> Self {
+        Self {
+            buf: [0; 256],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        // Only whole chars are copied into the buffer, see `write_str`
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+}
+
+impl core::fmt::Write for MessageBuffer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut n = s.len().min(self.buf.len() - self.len);
+        while !s.is_char_boundary(n) {
+            n -= 1;
+        }
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+pub fn outcome_of<T: TestOutcome>(outcome: &T) -> Outcome {
+    if outcome.is_success() {
+        Outcome::Passed
+    } else {
+        Outcome::Failed
+    }
+}
+
+/// Logs the result of a test which was repeated on the target via `#[repeat(n)]`
+pub fn report_repetitions<T: TestOutcome>(outcome: &T, repetition: u32, repeat: u32) {
+    if outcome.is_success() {
+        info!("Test passed all {} repetitions", repeat);
+    } else {
+        info!("Test failed in repetition {} of {}", repetition, repeat);
+    }
+}
+
+/// Set while the init function of a test is running, to tell a failing init apart from a failing test
+static INIT_RUNNING: AtomicBool = AtomicBool::new(false);
+
+pub fn enter_init() {
+    INIT_RUNNING.store(true, Ordering::Relaxed);
+    hosting::catch_init_panic();
+}
+
+pub fn leave_init() {
+    INIT_RUNNING.store(false, Ordering::Relaxed);
+}
+
+pub fn init_running() -> bool {
+    INIT_RUNNING.load(Ordering::Relaxed)
+}
+
+/// Unwraps the state returned by an init function returning `Result<State, E>`
+pub fn check_init_outcome<T, E>(outcome: Result<T, E>) -> T
+where
+    Result<(), E>: TestOutcome,
+{
+    match outcome {
+        Ok(state) => state,
+        Err(e) => {
+            let outcome: Result<(), E> = Err(e);
+            #[cfg(feature = "log")]
+            init_failed(format_args!("{:?}", outcome));
+            #[cfg(not(feature = "log"))]
+            {
+                // The error can only be formatted into the message with `log`, with `defmt` it's logged instead
+                error!("Init exited with Err(..): {:?}", outcome);
+                init_failed("Err(..)");
+            }
+        }
+    }
+}
+
+/// Tells the kind of init function given via `#[embedded_test::test(init = ...)]` apart by its return type.
+/// The macro can't see the function, and calls it like `init()`. So an async or fallible init function is rejected
+/// via [`check_freestanding_init`] instead of silently passing a future or a `Result` to the test.
+///
+/// Called as `(&&FreestandingInit::of(&state)).kind()` with all traits of [`freestanding_init`] in scope,
+/// the method of the most specific impl is picked by autoref.
+pub struct FreestandingInit<T>(core::marker::PhantomData<T>);
+
+impl<T> FreestandingInit<T> {
+    pub fn of(_state: &T) -> Self {
+        Self(core::marker::PhantomData)
+    }
+}
+
+pub mod freestanding_init {
+    use super::FreestandingInit;
+
+    pub struct AsyncInitFunction;
+    pub struct FallibleInitFunction;
+    pub struct InitFunction;
+
+    pub trait AsyncInitKind {
+        fn kind(&self) -> AsyncInitFunction {
+            AsyncInitFunction
+        }
+    }
+    impl<F: core::future::Future> AsyncInitKind for &FreestandingInit<F> {}
+
+    pub trait FallibleInitKind {
+        fn kind(&self) -> FallibleInitFunction {
+            FallibleInitFunction
+        }
+    }
+    impl<T, E> FallibleInitKind for &FreestandingInit<Result<T, E>> {}
+
+    pub trait InitKind {
+        fn kind(&self) -> InitFunction {
+            InitFunction
+        }
+    }
+    impl<T> InitKind for FreestandingInit<T> {}
+}
+
+#[diagnostic::on_unimplemented(
+    message = "the init function of a freestanding test can neither be async nor return a `Result`",
+    label = "`init = ...` is an `{Self}`",
+    note = "move the test into a `#[embedded_test::tests]` module with an `#[init]` function instead"
+)]
+pub trait SupportedFreestandingInit {}
+
+impl SupportedFreestandingInit for freestanding_init::InitFunction {}
+
+pub fn check_freestanding_init<K: SupportedFreestandingInit>(_kind: K) {}
+
+/// Reports that the init function failed (returned an error or panicked) and exits with [`EXIT_CODE_INIT_FAILED`]
+pub fn init_failed(message: impl core::fmt::Display) -> ! {
+    use core::fmt::Write;
+    let mut buffer = MessageBuffer::new();
+    let _ = write!(buffer, "{}", message);
+
+    clear_expected_reset();
+    error!("init failed: {}", buffer.as_str());
+    hosting::report(format_args!(
+        r#"{{"status":"init_failed","message":"{}"}}"#,
+        JsonEscaped(buffer.as_str())
+    ));
+    hosting::exit(EXIT_CODE_INIT_FAILED);
+}
+
+pub fn check_teardown_outcome<T: TestOutcome>(outcome: T) {
+    if !outcome.is_success() {
+        error!("Teardown exited with Err(..): {:?}", outcome);
+        clear_expected_reset();
+        hosting::abort();
+    }
+}
+
+/// Implemented for `T` only. The state returned by the init function is passed to the test (and teardown function)
+/// through it, so that a mismatch of the types is reported at the parameter. The types may be spelled differently,
+/// e.g. via an alias or the full path.
+#[diagnostic::on_unimplemented(
+    message = "this type must match the type `{T}` returned by the init function",
+    label = "expected `{T}`, found `{Self}`"
+)]
+pub trait InitState<T> {
+    fn from_state(state: T) -> Self;
+    fn from_state_ref(state: &T) -> &Self;
+    fn from_state_mut(state: &mut T) -> &mut Self;
+}
+
+impl<T> InitState<T> for T {
+    fn from_state(state: T) -> Self {
+        state
+    }
+    fn from_state_ref(state: &T) -> &Self {
+        state
+    }
+    fn from_state_mut(state: &mut T) -> &mut Self {
+        state
+    }
+}
+
+pub fn init_state<T, S: InitState<T>>(state: T) -> S {
+    S::from_state(state)
+}
+
+pub fn init_state_ref<T, S: InitState<T>>(state: &T) -> &S {
+    S::from_state_ref(state)
+}
+
+pub fn init_state_mut<T, S: InitState<T>>(state: &mut T) -> &mut S {
+    S::from_state_mut(state)
+}
+
+/// Like [`InitState`], for the value of a fixture passed to a test (or another fixture)
+#[diagnostic::on_unimplemented(
+    message = "this type must match the type `{T}` returned by the fixture",
+    label = "expected `{T}`, found `{Self}`"
+)]
+pub trait FixtureValue<T> {
+    fn from_fixture(value: T) -> Self;
+    fn from_fixture_ref(value: &T) -> &Self;
+    fn from_fixture_mut(value: &mut T) -> &mut Self;
+}
+
+impl<T> FixtureValue<T> for T {
+    fn from_fixture(value: T) -> Self {
+        value
+    }
+    fn from_fixture_ref(value: &T) -> &Self {
+        value
+    }
+    fn from_fixture_mut(value: &mut T) -> &mut Self {
+        value
+    }
+}
+
+pub fn fixture_value<T, F: FixtureValue<T>>(value: T) -> F {
+    F::from_fixture(value)
+}
+
+pub fn fixture_value_ref<T, F: FixtureValue<T>>(value: &T) -> &F {
+    F::from_fixture_ref(value)
+}
+
+pub fn fixture_value_mut<T, F: FixtureValue<T>>(value: &mut T) -> &mut F {
+    F::from_fixture_mut(value)
+}
+
+/// Creates the `TestInfo` static of a test (it's `#[non_exhaustive]`, so it can't be built by the macro directly)
+pub const fn test_info(
+    name: &'static str,
+    module_path: &'static str,
+    timeout: Option<core::time::Duration>,
+    tags: &'static [&'static str],
+    should_panic: bool,
+) -> TestInfo {
+    TestInfo {
+        name,
+        module_path,
+        timeout,
+        tags,
+        should_panic,
+    }
+}
+
+/// The test which is currently running
+static CURRENT_TEST: AtomicPtr<TestInfo> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Called by the entrypoint of a test (and by the group runner before each test of a group)
+pub fn enter_test(info: &'static TestInfo) {
+    CURRENT_TEST.store(info as *const TestInfo as *mut TestInfo, Ordering::Relaxed);
+}
+
+pub fn current_test() -> Option<&'static TestInfo> {
+    let info = CURRENT_TEST.load(Ordering::Relaxed);
+    // SAFETY: Only ever set to a `&'static TestInfo` via `enter_test`
+    unsafe { info.as_ref() }
+}
+
+/// Passed to an `info: &TestInfo` parameter of an init, test or teardown function
+pub fn current_test_info() -> &'static TestInfo {
+    current_test().expect("no test is running")
+}
+
+/// Position (within its `#[group_init]` group) of the test which was started by the runner
+static GROUP_POSITION: AtomicUsize = AtomicUsize::new(0);
+/// Set by the `run_group` command: The following tests of the group are run as well, without a reset in between
+static GROUP_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Called by the entrypoint of a test, which is part of a `#[group_init]` group
+pub fn enter_group(position: usize) {
+    GROUP_POSITION.store(position, Ordering::Relaxed);
+}
+
+/// Returns whether the test at `position` of the group has to be run in this boot
+pub fn group_runs(position: usize) -> bool {
+    let first = GROUP_POSITION.load(Ordering::Relaxed);
+    position == first || (position > first && GROUP_MODE.load(Ordering::Relaxed))
+}
+
+/// Checks the outcome of a test of a `#[group_init]` group. On success, the group continues with the next test.
+/// A failure aborts, the runner fails the remaining tests of the group then.
+pub fn check_group_outcome<T: TestOutcome>(outcome: T, name: &str) {
+    if !outcome.is_success() {
+        info!("Test {} exited with Err(..): {:?}", name, outcome);
+        hosting::abort();
+    }
+    info!("Test {} exited with () or Ok(..)", name);
+    if GROUP_MODE.load(Ordering::Relaxed) {
+        hosting::report(format_args!(
+            r#"{{"status":"passed","test":"{}"}}"#,
+            JsonEscaped(name)
+        ));
+    }
+}
+
+pub use crate::__check_bench_supported as check_bench_supported;
+
+/// Creates the bencher passed to a `#[bench]` function
+pub fn bencher(warmup: u32, iterations: u32) -> Bencher {
+    Bencher::new(warmup, iterations)
+}
+
+/// Checks the outcome of a `#[bench]` function and reports the statistics of the cycles measured by its bencher
+pub fn check_bench_outcome<T: TestOutcome>(outcome: T, bencher: &mut Bencher) -> ! {
+    if !outcome.is_success() {
+        check_outcome(outcome);
+    }
+    let Some(stats) = bencher.statistics() else {
+        error!("Benchmark exited without calling `Bencher::iter`");
+        hosting::abort();
+    };
+    info!(
+        "Benchmark: min {}, median {}, mean {}, max {} {} ({} iterations)",
+        stats.min,
+        stats.median,
+        stats.mean,
+        stats.max,
+        crate::bench::UNIT,
+        stats.iterations
+    );
+    hosting::report(format_args!(
+        r#"{{"status":"bench","unit":"{}","iterations":{},"min":{},"median":{},"mean":{},"max":{}}}"#,
+        crate::bench::UNIT,
+        stats.iterations,
+        stats.min,
+        stats.median,
+        stats.mean,
+        stats.max
+    ));
+    hosting::exit(0);
+}
+
+/// Skips an ignored test of a `#[group_init]` group. The group continues with the next test.
+/// If the runner started the ignored test on its own, it's reported as skipped.
+pub fn skip_group_test(name: &str, reason: &str) {
+    if !GROUP_MODE.load(Ordering::Relaxed) {
+        skip(reason);
+    }
+    info!("Test {} ignored: {}", name, reason);
+    hosting::report(format_args!(
+        r#"{{"status":"ignored","test":"{}","reason":"{}"}}"#,
+        JsonEscaped(name),
+        JsonEscaped(reason)
+    ));
+}
+
+/// Index of the stage of a multi-stage test, as given by the `run_stage` command
+static STAGE: AtomicU32 = AtomicU32::new(0);
+
+pub fn stage() -> u32 {
+    STAGE.load(Ordering::Relaxed)
+}
+
+/// Called by the entrypoint of a multi-stage test, before the init function
+pub fn check_stage(stages: u32) {
+    let stage = stage();
+    if stage >= stages {
+        error!(
+            "Stage {} was requested, but the test only has {} stages",
+            stage, stages
+        );
+        hosting::abort();
+    }
+    info!("Running stage {} of {}", stage + 1, stages);
+}
+
+/// Checks the outcome of a stage of a multi-stage test. Every stage but the last one exits with
+/// [`EXIT_CODE_EXPECT_RESET`] on success, so that the runner resets the device and runs the next stage.
+pub fn check_stage_outcome<T: TestOutcome>(outcome: T, stages: u32) -> ! {
+    let stage = stage();
+    if outcome.is_success() && stage + 1 < stages {
+        info!("Stage {} of {} passed", stage + 1, stages);
+        expect_reset();
+        hosting::exit(EXIT_CODE_EXPECT_RESET);
+    }
+    check_outcome(outcome)
+}
+
+/// Tells the runner that the device is going to reset, which ends the current stage of a multi-stage test
+pub fn expect_reset() {
+    hosting::report(format_args!(
+        r#"{{"status":"expect_reset","stage":{}}}"#,
+        stage()
+    ));
+}
+
+/// Identifies the test which was started by the runner (the address or name given with the command)
+static STARTED_TEST: AtomicU32 = AtomicU32::new(0);
+
+/// Set by a `#[should_reset]` test to the id of the test. It survives the reset, so that the next boot can report it.
+#[cfg_attr(not(feature = "std"), link_section = ".uninit.embedded_test")]
+static RESET_EXPECTED: Retained<u32> = Retained::new();
+
+/// FNV-1a hash of the address or name of a test
+fn test_id(test: &str) -> u32 {
+    test.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Called by the runner's command with the address (or name) of the test to run.
+/// If the same `#[should_reset]` test was started before the last reset, the reset is reported instead of running it again.
+fn check_reset_observed(test: &str) {
+    let id = test_id(test);
+    STARTED_TEST.store(id, Ordering::Relaxed);
+    let expected = RESET_EXPECTED.load();
+    RESET_EXPECTED.clear();
+    if expected == Some(id) {
+        info!("Test reset the device as expected");
+        hosting::report(format_args!(r#"{{"status":"reset_observed"}}"#));
+        hosting::exit(0);
+    }
+}
+
+/// Called by a `#[should_reset]` test right before the test function. The runner starts the test again after the reset.
+pub fn expect_device_reset() {
+    RESET_EXPECTED.store(STARTED_TEST.load(Ordering::Relaxed));
+}
+
+/// Called on every path which ends a test without a reset (panic, fault, failed init, ...),
+/// so that a failed `#[should_reset]` test is not reported as passed when it's run again
+pub fn clear_expected_reset() {
+    RESET_EXPECTED.clear();
+}
+
+pub fn check_outcome_expecting_reset<T: TestOutcome>(outcome: T) -> ! {
+    clear_expected_reset();
+    let _ = outcome;
+    error!("Test exited without resetting the device, but a reset was expected");
+    hosting::abort();
+}
+
 // Ariel OS invokes the `__embedded_test_entry` function directly
 // Otherwise we export it as `main` function.
 #[cfg_attr(not(feature = "_ariel"), export_name = "main")]
@@ -73,15 +623,49 @@ unsafe extern "C" fn __embedded_test_start() -> ! {
         "run" => {
             let test_name = args.next().expect("test name missing");
             let test_name = test_name.expect("test name contains non-utf8 character");
+            check_reset_observed(test_name);
             export::hosting::run_test(test_name);
         }
         "run_addr" => {
             let addr = args.next().expect("addr missing");
             let addr = addr.expect("addr contains non-utf8 character");
+            check_reset_observed(addr);
             let addr: usize = addr.parse().expect("invalid number");
             let test_invoker: fn() -> ! = unsafe { core::mem::transmute(addr) };
             test_invoker();
         }
+        "run_group" => {
+            // Runs the given test and all following tests of its `#[group_init]` group in a single boot
+            GROUP_MODE.store(true, Ordering::Relaxed);
+            let test = args.next().expect("addr missing");
+            let test = test.expect("addr contains non-utf8 character");
+            check_reset_observed(test);
+            match test.parse::<usize>() {
+                Ok(addr) => {
+                    let test_invoker: fn() -> ! = unsafe { core::mem::transmute(addr) };
+                    test_invoker();
+                }
+                // On std, tests are referred to by name
+                Err(_) => export::hosting::run_test(test),
+            }
+        }
+        "run_stage" => {
+            // Runs a single stage of a multi-stage test, the runner resets the device between the stages
+            let test = args.next().expect("addr missing");
+            let test = test.expect("addr contains non-utf8 character");
+            check_reset_observed(test);
+            let stage = args.next().expect("stage missing");
+            let stage = stage.expect("stage contains non-utf8 character");
+            STAGE.store(stage.parse().expect("invalid stage"), Ordering::Relaxed);
+            match test.parse::<usize>() {
+                Ok(addr) => {
+                    let test_invoker: fn() -> ! = unsafe { core::mem::transmute(addr) };
+                    test_invoker();
+                }
+                // On std, tests are referred to by name
+                Err(_) => export::hosting::run_test(test),
+            }
+        }
         _ => {
             error!("Unknown command: {}", command);
             export::hosting::abort();
@@ -0,0 +1,225 @@
+//! Exception handlers, which end the running test when the CPU faults (feature `fault-handler`)
+//!
+//! An unexpected fault is logged with its registers (CFSR, HFSR, MMFAR, BFAR, stacked PC and LR on Cortex-M;
+//! mcause, mepc and mtval on RISC-V), reported via semihosting stderr (`"status":"fault"`) and aborts the test.
+
+use crate::export::{self, hosting, JsonEscaped};
+use core::cell::Cell;
+
+struct ExpectedFault(Cell<Option<Option<&'static str>>>);
+
+// SAFETY: Tests are executed single threaded. The value is set once at the beginning of a test.
+unsafe impl Sync for ExpectedFault {}
+
+/// Set by a `#[should_fault]` test, optionally with the expected class of the fault (e.g. `MemManage`)
+static EXPECTED_FAULT: ExpectedFault = ExpectedFault(Cell::new(None));
+
+/// Registers that the current test is expected to fault, optionally with the given class of fault.
+/// Inlined, so that the check of the exception dispatch on RISC-V only applies to binaries with `#[should_fault]` tests.
+#[inline(always)]
+pub fn expect_fault(kind: Option<&'static str>) {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    riscv::check_exception_dispatch();
+    EXPECTED_FAULT.0.set(Some(kind));
+}
+
+/// Formats the registers as the members of a JSON object (without the surrounding braces)
+struct JsonRegisters<'a>(&'a [(&'static str, usize)]);
+
+impl core::fmt::Display for JsonRegisters<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, (name, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, r#""{}":{}"#, name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Called by the exception handlers with the class of the fault and the registers describing it
+fn fault(kind: &'static str, registers: &[(&'static str, usize)]) -> ! {
+    export::clear_expected_reset();
+    if export::init_running() {
+        log_registers(kind, registers);
+        export::init_failed(kind);
+    }
+
+    match EXPECTED_FAULT.0.get() {
+        Some(expected) if expected.is_none_or(|expected| expected == kind) => {
+            info!("Test faulted with {} as expected", kind);
+            hosting::exit(0);
+        }
+        Some(Some(expected)) => {
+            error!("Test faulted with {}, but {} was expected", kind, expected);
+        }
+        _ => {}
+    }
+
+    log_registers(kind, registers);
+    hosting::report(format_args!(
+        r#"{{"status":"fault","kind":"{}","registers":{{{}}}}}"#,
+        JsonEscaped(kind),
+        JsonRegisters(registers)
+    ));
+    hosting::abort();
+}
+
+fn log_registers(kind: &str, registers: &[(&'static str, usize)]) {
+    error!("====================== {} ======================", kind);
+    for (name, value) in registers {
+        error!("{}: {:#010x}", name, value);
+    }
+}
+
+#[cfg(cortex_m)]
+mod cortex_m {
+    /// Configurable Fault Status Register
+    #[cfg(not(armv6m))]
+    const CFSR: *const u32 = 0xE000_ED28 as *const u32;
+    /// HardFault Status Register
+    #[cfg(not(armv6m))]
+    const HFSR: *const u32 = 0xE000_ED2C as *const u32;
+    /// MemManage Fault Address Register
+    #[cfg(not(armv6m))]
+    const MMFAR: *const u32 = 0xE000_ED34 as *const u32;
+    /// BusFault Address Register
+    #[cfg(not(armv6m))]
+    const BFAR: *const u32 = 0xE000_ED38 as *const u32;
+
+    // Passes the stack frame of the exception to `hard_fault` (via r0), like the trampoline of cortex-m-rt
+    core::arch::global_asm!(
+        ".section .text.embedded_test.HardFault, \"ax\"",
+        ".global HardFault",
+        ".type HardFault, %function",
+        ".thumb_func",
+        "HardFault:",
+        "mov r0, lr",
+        "movs r1, #4",
+        "tst r0, r1",
+        "bne 0f",
+        "mrs r0, MSP",
+        "b {handler}",
+        "0:",
+        "mrs r0, PSP",
+        "b {handler}",
+        handler = sym hard_fault,
+    );
+
+    // The configurable faults are handled alike, in case their handlers are enabled (via SHCSR)
+    #[cfg(not(armv6m))]
+    core::arch::global_asm!(
+        ".global MemoryManagement",
+        ".global BusFault",
+        ".global UsageFault",
+        ".thumb_set MemoryManagement, HardFault",
+        ".thumb_set BusFault, HardFault",
+        ".thumb_set UsageFault, HardFault",
+    );
+
+    /// The configurable faults (MemManage, BusFault and UsageFault) escalate to a HardFault, unless their handlers are enabled.
+    /// Either way, the CFSR tells them apart.
+    unsafe extern "C" fn hard_fault(frame: *const u32) -> ! {
+        // Stacked registers: r0, r1, r2, r3, r12, lr, pc, xpsr
+        let (lr, pc) = unsafe { (frame.add(5).read_volatile(), frame.add(6).read_volatile()) };
+        fault(lr as usize, pc as usize)
+    }
+
+    #[cfg(not(armv6m))]
+    fn fault(lr: usize, pc: usize) -> ! {
+        let (cfsr, hfsr, mmfar, bfar) = unsafe {
+            (
+                CFSR.read_volatile(),
+                HFSR.read_volatile(),
+                MMFAR.read_volatile(),
+                BFAR.read_volatile(),
+            )
+        };
+        let kind = if cfsr & 0x0000_00ff != 0 {
+            "MemManage"
+        } else if cfsr & 0x0000_ff00 != 0 {
+            "BusFault"
+        } else if cfsr & 0xffff_0000 != 0 {
+            "UsageFault"
+        } else {
+            "HardFault"
+        };
+        super::fault(
+            kind,
+            &[
+                ("cfsr", cfsr as usize),
+                ("hfsr", hfsr as usize),
+                ("mmfar", mmfar as usize),
+                ("bfar", bfar as usize),
+                ("pc", pc),
+                ("lr", lr),
+            ],
+        )
+    }
+
+    #[cfg(armv6m)]
+    fn fault(lr: usize, pc: usize) -> ! {
+        super::fault("HardFault", &[("pc", pc), ("lr", lr)])
+    }
+}
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+mod riscv {
+    use core::arch::asm;
+
+    /// Defines the handlers which riscv-rt calls for the individual exceptions (they default to `ExceptionHandler`).
+    /// `ExceptionHandler` itself is left to the runtime, esp-hal e.g. defines it to panic on every exception.
+    macro_rules! exception_handlers {
+        ($($kind:ident),* $(,)?) => {
+            $(
+                #[no_mangle]
+                unsafe extern "C" fn $kind(_trap_frame: *const u8) -> ! {
+                    fault(stringify!($kind))
+                }
+            )*
+        };
+    }
+
+    exception_handlers!(
+        InstructionMisaligned,
+        InstructionFault,
+        IllegalInstruction,
+        Breakpoint,
+        LoadMisaligned,
+        LoadFault,
+        StoreMisaligned,
+        StoreFault,
+        UserEnvCall,
+        SupervisorEnvCall,
+        MachineEnvCall,
+        InstructionPageFault,
+        LoadPageFault,
+        StorePageFault,
+    );
+
+    fn fault(kind: &'static str) -> ! {
+        let (mcause, mepc, mtval): (usize, usize, usize);
+        unsafe {
+            asm!("csrr {}, mcause", out(reg) mcause);
+            asm!("csrr {}, mepc", out(reg) mepc);
+            asm!("csrr {}, mtval", out(reg) mtval);
+        }
+        super::fault(
+            kind,
+            &[("mcause", mcause), ("mepc", mepc), ("mtval", mtval)],
+        )
+    }
+
+    /// Makes the link fail with a readable symbol name, if the exceptions are not dispatched by riscv-rt.
+    /// esp-hal handles the exceptions itself, so a `#[should_fault]` test would never see its fault (see `embedded-test.x`).
+    #[inline(always)]
+    pub fn check_exception_dispatch() {
+        extern "C" {
+            static embedded_test_should_fault_needs_riscv_rt: u8;
+        }
+        core::hint::black_box(core::ptr::addr_of!(
+            embedded_test_should_fault_needs_riscv_rt
+        ));
+    }
+}
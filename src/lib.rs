@@ -1,10 +1,20 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(target_arch = "xtensa", feature(asm_experimental_arch))]
 #![allow(clippy::needless_doctest_main)]
 #![cfg_attr(not(doctest), doc = include_str!("../README.md"))]
 
 mod fmt;
 
-pub use embedded_test_macros::{setup, tests};
+mod bench;
+#[cfg(feature = "fault-handler")]
+mod fault;
+
+pub use bench::Bencher;
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+
+pub use embedded_test_macros::{instantiate_suite, setup, suite, test, tests};
 
 #[cfg(all(feature = "panic-handler", not(feature = "_ariel")))]
 #[panic_handler]
@@ -13,9 +23,40 @@ fn panic(info: &core::panic::PanicInfo) -> ! {
 
     error!("{}", info);
 
+    export::clear_expected_reset();
+
+    if export::init_running() {
+        export::init_failed(info.message());
+    }
+
+    if let Some(expected) = export::hosting::expected_panic() {
+        export::check_panic_message(info.message(), expected);
+    }
+
     semihosting::process::abort()
 }
 
+/// Skips the currently running test. The test will be reported as ignored with the given reason.
+///
+/// Use this if a test can only decide at runtime whether it applies (e.g. an optional peripheral is not
+/// populated on the board). The teardown function is not invoked for a skipped test.
+///
+/// ```rust,ignore
+/// #[test]
+/// fn reads_sensor() {
+///     if !sensor_populated() {
+///         embedded_test::skip!("sensor not populated on this board revision");
+///     }
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! skip {
+    ($reason:expr $(,)?) => {
+        $crate::export::skip($reason)
+    };
+}
+
 /// Private implementation details used by the proc macro.
 /// WARNING: This API is not stable and may change at any time.
 #[doc(hidden)]
@@ -57,6 +98,150 @@ pub trait TestOutcome: sealed::Sealed {
     fn is_success(&self) -> bool;
 }
 
+/// The outcome of a test, as passed to the `#[teardown]` function of a test module.
+///
+/// A panicking test never reaches the teardown function, so this only distinguishes between a test
+/// which returned successfully and a test which returned an `Err(..)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Outcome {
+    /// The test returned `()` or `Ok(..)`
+    Passed,
+    /// The test returned `Err(..)`
+    Failed,
+}
+
+impl Outcome {
+    pub fn is_success(&self) -> bool {
+        *self == Outcome::Passed
+    }
+}
+
+/// Information about a test, as passed to init, test and teardown functions which take an `info: &TestInfo` parameter.
+///
+/// The currently running test can also be retrieved via [`current_test`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct TestInfo {
+    /// Name of the test, without the module path (e.g. `it_works` or `parameterized::case_1`)
+    pub name: &'static str,
+    /// Module path of the test (e.g. `my_crate::tests`)
+    pub module_path: &'static str,
+    /// Timeout of the test, if one was set via `#[timeout]` or `default_timeout`
+    pub timeout: Option<core::time::Duration>,
+    /// Tags of the test, including the tags of its module
+    pub tags: &'static [&'static str],
+    /// Whether the test is expected to panic (`#[should_panic]`, with or without an expected message)
+    pub should_panic: bool,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for TestInfo {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=str}::{=str}", self.module_path, self.name)
+    }
+}
+
+/// Returns the test which is currently running, or `None` if no test has been started yet (e.g. in the setup function).
+///
+/// ```rust,ignore
+/// if let Some(test) = embedded_test::current_test() {
+///     log::info!("[{}] sensor value: {}", test.name, value);
+/// }
+/// ```
+pub fn current_test() -> Option<&'static TestInfo> {
+    export::current_test()
+}
+
+/// Returns the index (starting at 0) of the stage of a multi-stage test (`#[test(stages = n)]`) which is running.
+///
+/// The runner resets the device between the stages. Use [`Retained`] to pass data from one stage to the next.
+/// A multi-stage test is exported with `stages`, the runner starts a stage via the `run_stage <addr> <index>` command.
+/// Every stage but the last one exits with a distinct exit code (`4`) and reports `"status":"expect_reset"`.
+///
+/// ```rust,ignore
+/// #[test(stages = 2)]
+/// fn survives_reset() {
+///     match embedded_test::stage() {
+///         0 => write_config(),
+///         _ => assert!(config_is_valid()),
+///     }
+/// }
+/// ```
+pub fn stage() -> u32 {
+    export::stage()
+}
+
+/// Tells the runner that the device is about to reset itself (e.g. entering deep sleep or waiting for the watchdog),
+/// which ends the current stage of a multi-stage test. The runner then runs the next stage after the reset.
+///
+/// A stage which returns successfully does not need to call this, the runner resets the device itself.
+pub fn expect_reset() {
+    export::expect_reset()
+}
+
+/// A value which survives the resets between the stages of a multi-stage test.
+///
+/// The static must be placed in a section which is not initialized at startup, e.g. the `.uninit` section
+/// provided by the linker scripts of `cortex-m-rt` and `riscv-rt`:
+///
+/// ```rust,ignore
+/// #[unsafe(link_section = ".uninit.embedded_test")]
+/// static BOOT_COUNT: embedded_test::Retained<u32> = embedded_test::Retained::new();
+/// ```
+///
+/// [`Retained::load`] returns `None` until a value was stored (and after a power cycle, with high probability).
+/// On `std`, every stage runs in a new process, so nothing is retained.
+pub struct Retained<T: Copy> {
+    marker: UnsafeCell<u32>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: Tests are executed single threaded
+unsafe impl<T: Copy + Send> Sync for Retained<T> {}
+
+impl<T: Copy> Retained<T> {
+    /// Marks a stored value. Any other value of the marker (e.g. random RAM content after a power cycle) means there is none.
+    const MARKER: u32 = 0xE7E5_7A6E;
+
+    pub const fn new() -> Self {
+        Self {
+            marker: UnsafeCell::new(0),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    pub fn store(&self, value: T) {
+        // SAFETY: Tests are executed single threaded. Volatile, since the value is only read after a reset.
+        unsafe {
+            core::ptr::write_volatile(self.value.get(), MaybeUninit::new(value));
+            core::ptr::write_volatile(self.marker.get(), Self::MARKER);
+        }
+    }
+
+    pub fn load(&self) -> Option<T> {
+        // SAFETY: The value has been written by `store` if the marker is set
+        unsafe {
+            if core::ptr::read_volatile(self.marker.get()) == Self::MARKER {
+                Some(core::ptr::read_volatile(self.value.get()).assume_init())
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn clear(&self) {
+        // SAFETY: Tests are executed single threaded
+        unsafe { core::ptr::write_volatile(self.marker.get(), 0) }
+    }
+}
+
+impl<T: Copy> Default for Retained<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TestOutcome for () {
     fn is_success(&self) -> bool {
         true
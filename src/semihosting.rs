@@ -1,5 +1,14 @@
+use core::cell::Cell;
 use semihosting::experimental::env::Args;
 use semihosting::io;
+use semihosting::io::Write;
+
+struct ExpectedPanic(Cell<Option<&'static str>>);
+
+// SAFETY: Tests are executed single threaded. The value is set once at the beginning of a test.
+unsafe impl Sync for ExpectedPanic {}
+
+static EXPECTED_PANIC: ExpectedPanic = ExpectedPanic(Cell::new(None));
 
 pub fn args() -> io::Result<Args<1024>> {
     semihosting::experimental::env::args::<1024>()
@@ -13,6 +22,26 @@ pub fn exit(code: i32) -> ! {
     semihosting::process::exit(code)
 }
 
+/// Registers the message substring the panic handler should expect for the current test.
+pub fn expect_panic(expected: &'static str) {
+    EXPECTED_PANIC.0.set(Some(expected));
+}
+
+pub fn expected_panic() -> Option<&'static str> {
+    EXPECTED_PANIC.0.get()
+}
+
+/// Writes a single line to the host's stderr, to report details (e.g. a skip reason) to the runner.
+pub fn report(line: core::fmt::Arguments) {
+    if let Ok(mut stderr) = io::stderr() {
+        let _ = stderr.write_fmt(line);
+        let _ = stderr.write_all(b"\n");
+    }
+}
+
+/// A panic of the init function is reported by the panic handler
+pub fn catch_init_panic() {}
+
 pub fn run_test(_test: &str) -> ! {
     error!("Running test by name is no longer supported by embedded-test. Please upgrade probe-rs to the latest version");
     abort();
@@ -1,5 +1,5 @@
 use core::convert::Infallible;
-use std::sync::LazyLock;
+use std::sync::{LazyLock, Once};
 
 pub use linkme;
 pub use linkme::distributed_slice;
@@ -18,8 +18,37 @@ pub struct Test {
     #[serde(skip)]
     pub function: fn() -> !,
     pub should_panic: bool,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    pub should_reset: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub should_reset_within_ms: Option<u32>,
     pub ignored: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_reason: Option<&'static str>,
     pub timeout: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<u32>,
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    pub repeat_on_target: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stages: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_index: Option<usize>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub tags: &'static [&'static str],
+    #[serde(
+        skip_serializing_if = "<[_]>::is_empty",
+        serialize_with = "ser_test_meta"
+    )]
+    pub meta: &'static [(&'static str, &'static str)],
 }
 
 fn strip_crate_name(name: &str) -> Option<&str> {
@@ -31,6 +60,13 @@ fn ser_test_name<S: serde::Serializer>(name: &'static str, s: S) -> Result<S::Ok
     s.serialize_str(name)
 }
 
+fn ser_test_meta<S: serde::Serializer>(
+    meta: &&'static [(&'static str, &'static str)],
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    s.collect_map(meta.iter().copied())
+}
+
 #[distributed_slice]
 pub static TESTS: [Test];
 
@@ -57,6 +93,44 @@ pub fn exit(code: i32) -> ! {
     std::process::exit(code)
 }
 
+/// Writes a single line to stderr, to report details (e.g. a skip reason) to the runner.
+pub fn report(line: core::fmt::Arguments) {
+    eprintln!("{line}");
+}
+
+/// Installs a panic hook which checks the panic message against the expected substring.
+pub fn expect_panic(expected: &'static str) {
+    std::panic::set_hook(Box::new(move |info| {
+        crate::export::check_panic_message(panic_message(info), expected);
+    }));
+}
+
+fn panic_message<'a>(info: &'a std::panic::PanicHookInfo) -> &'a str {
+    let payload = info.payload();
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.as_str()
+    } else {
+        ""
+    }
+}
+
+/// Installs a panic hook which reports a panic of the init function as such.
+/// It's installed once, in front of the hook which was set before (e.g. by [`expect_panic`]).
+pub fn catch_init_panic() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if crate::export::init_running() {
+                crate::export::init_failed(panic_message(info));
+            }
+            previous(info);
+        }));
+    });
+}
+
 pub fn run_test(test_name: &str) -> ! {
     let test = TESTS
         .iter()
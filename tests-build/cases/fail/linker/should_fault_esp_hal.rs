@@ -0,0 +1,27 @@
+/*
+```cargo
+[dependencies]
+esp-hal = { version = "=1.0.0", features = ["esp32c6", "unstable"] }
+embedded-test = { path = "../../../..", features = ["fault-handler"] }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[init]
+    fn init() -> esp_hal::peripherals::Peripherals {
+        esp_hal::init(esp_hal::Config::default())
+    }
+
+    #[test]
+    #[should_fault]
+    fn faults(_peripherals: esp_hal::peripherals::Peripherals) {
+        unsafe { core::arch::asm!("unimp") }
+    }
+}
@@ -0,0 +1,19 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[meta(bench)]
+    fn with_meta() {}
+}
@@ -0,0 +1,21 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[should_panic(message = "boom")]
+    fn should_panic() {
+        panic!("boom")
+    }
+}
@@ -0,0 +1,18 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::suite(default_timeout = 5)]
+mod flash_suite {
+    #[test]
+    fn works() {}
+}
@@ -0,0 +1,22 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use embedded_test::Bencher;
+
+    #[bench(iterations = 1000)]
+    fn too_many(bencher: &mut Bencher) {
+        bencher.iter(|| ());
+    }
+}
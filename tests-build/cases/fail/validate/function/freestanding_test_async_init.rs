@@ -0,0 +1,19 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+mod tests {
+    async fn init() {}
+
+    #[embedded_test::test(init = init)]
+    fn async_init() {}
+}
@@ -0,0 +1,21 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+mod tests {
+    fn init() -> Result<u32, ()> {
+        Ok(42)
+    }
+
+    #[embedded_test::test(init = init)]
+    fn fallible_init(_state: u32) {}
+}
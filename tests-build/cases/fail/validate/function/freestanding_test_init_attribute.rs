@@ -0,0 +1,18 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+mod tests {
+    #[embedded_test::test]
+    #[test(init = init)]
+    fn takes_state(_state: u32) {}
+}
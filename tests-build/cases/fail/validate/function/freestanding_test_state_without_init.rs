@@ -0,0 +1,17 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+mod tests {
+    #[embedded_test::test]
+    fn takes_state(_state: u32) {}
+}
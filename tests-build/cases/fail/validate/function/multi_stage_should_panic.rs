@@ -0,0 +1,21 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test(stages = 2)]
+    #[should_panic]
+    fn panics_after_reset() {
+        assert!(embedded_test::stage() == 0)
+    }
+}
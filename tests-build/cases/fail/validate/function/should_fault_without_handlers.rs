@@ -0,0 +1,21 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[should_fault]
+    fn faults() {
+        let _ = unsafe { core::ptr::read_volatile(0xFFFF_FFF0 as *const u32) };
+    }
+}
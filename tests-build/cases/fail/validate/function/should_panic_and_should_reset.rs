@@ -0,0 +1,22 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[should_panic]
+    #[should_reset]
+    fn panics_or_resets() {
+        assert!(false)
+    }
+}
@@ -0,0 +1,27 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../..", default-features = false, features = ["semihosting"] }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn overflows() {
+        panic!("overflow")
+    }
+}
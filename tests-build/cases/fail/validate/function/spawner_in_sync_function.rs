@@ -0,0 +1,22 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    struct Spawner;
+
+    #[test]
+    fn takes_spawner(_spawner: Spawner) {
+        assert!(true)
+    }
+}
@@ -0,0 +1,20 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[test_case(1, 2)]
+    #[test_case(3)]
+    fn test_case(_a: u32, _b: u32) {}
+}
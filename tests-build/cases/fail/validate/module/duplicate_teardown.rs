@@ -0,0 +1,24 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[teardown]
+    fn teardown1() {}
+
+    #[teardown]
+    fn teardown2() {}
+
+    #[test]
+    fn test() {}
+}
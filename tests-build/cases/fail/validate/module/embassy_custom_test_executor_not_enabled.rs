@@ -0,0 +1,24 @@
+/*
+```cargo
+[dependencies]
+embassy-executor = { version = "0.9", features = ["executor-thread","arch-riscv32"] }
+esp-rtos = { version = "0.2.0", features = ["embassy", "esp32c6", "log-04"] }
+esp-hal = { version = "=1.0.0", features = ["esp32c6", "unstable"] }
+embedded-test = { path = "../../../../..", features = ["embassy"] }
+
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test(executor = esp_rtos::embassy::Executor::new())]
+    async fn own_executor() {
+        assert!(true)
+    }
+}
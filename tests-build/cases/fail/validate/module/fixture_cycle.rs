@@ -0,0 +1,31 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    struct Dma;
+    struct Uart;
+
+    #[fixture]
+    fn dma(uart: Uart) -> Dma {
+        Dma
+    }
+
+    #[fixture]
+    fn uart(dma: Dma) -> Uart {
+        Uart
+    }
+
+    #[test]
+    fn test(uart: Uart) {}
+}
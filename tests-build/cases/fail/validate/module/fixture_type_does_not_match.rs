@@ -0,0 +1,23 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[fixture]
+    fn dma() -> u32 {
+        42
+    }
+
+    #[test]
+    fn test(dma: u8) {}
+}
@@ -0,0 +1,24 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[init]
+    fn init() {}
+
+    #[group_init]
+    fn modem() {}
+
+    #[test]
+    fn test() {}
+}
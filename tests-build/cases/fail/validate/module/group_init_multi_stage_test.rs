@@ -0,0 +1,25 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[group_init]
+    fn bus() -> u32 {
+        0
+    }
+
+    #[test(stages = 2)]
+    fn survives_reset(_bus: &u32) {
+        assert!(true)
+    }
+}
@@ -0,0 +1,26 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[group_init]
+    fn bus() -> u32 {
+        0
+    }
+
+    #[test]
+    #[retry(3)]
+    fn flaky(_bus: &u32) {
+        assert!(true)
+    }
+}
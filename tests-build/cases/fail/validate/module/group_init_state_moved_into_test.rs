@@ -0,0 +1,25 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    struct Modem;
+
+    #[group_init]
+    fn modem() -> Modem {
+        Modem
+    }
+
+    #[test]
+    fn test(_modem: Modem) {}
+}
@@ -0,0 +1,26 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[init]
+    fn init() -> f64 {
+        3.14
+    }
+
+    #[teardown]
+    fn teardown(_state: u32, _outcome: embedded_test::Outcome) {}
+
+    #[test]
+    fn test(_state: &f64) {}
+}
@@ -0,0 +1,28 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    struct Context;
+
+    #[init]
+    fn init() -> Context {
+        Context
+    }
+
+    #[teardown]
+    fn teardown(_state: Context, _outcome: embedded_test::Outcome) {}
+
+    #[test]
+    fn takes_state(_state: Context) {}
+}
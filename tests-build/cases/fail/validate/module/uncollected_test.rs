@@ -0,0 +1,19 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+#![deny(deprecated)] // turns the warning into an error
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[cfg_attr(not(rust_analyzer), test)]
+    fn test() {}
+}
@@ -0,0 +1,27 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    struct Uart;
+
+    #[fixture]
+    fn uart(_dma: u32) -> Uart {
+        Uart
+    }
+
+    #[test]
+    fn test(uart: Uart) {
+        let _ = uart;
+    }
+}
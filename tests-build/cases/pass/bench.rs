@@ -0,0 +1,38 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use embedded_test::Bencher;
+
+    #[init]
+    fn init() -> [u8; 64] {
+        [0xA5; 64]
+    }
+
+    #[bench]
+    fn checksum(data: [u8; 64], bencher: &mut Bencher) {
+        bencher.iter(|| data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)));
+    }
+
+    #[bench(iterations = 256, warmup = 0)]
+    #[test_case(8)]
+    #[test_case(64)]
+    fn count_ones(bencher: &mut Bencher, n: u32) -> Result<(), ()> {
+        bencher.iter(|| (0..n).map(u32::count_ones).sum::<u32>());
+        Ok(())
+    }
+
+    #[test]
+    fn it_works() {}
+}
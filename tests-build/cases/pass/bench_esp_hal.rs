@@ -0,0 +1,29 @@
+/*
+```cargo
+[dependencies]
+esp-hal = { version = "=1.0.0", features = ["esp32c6", "unstable"] }
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use embedded_test::Bencher;
+
+    #[init]
+    fn init() -> esp_hal::peripherals::Peripherals {
+        esp_hal::init(esp_hal::Config::default())
+    }
+
+    // The ESP32-C6 has no `mcycle`, the benchmark reads its performance counter instead
+    #[bench]
+    fn sum(_peripherals: esp_hal::peripherals::Peripherals, bencher: &mut Bencher) {
+        bencher.iter(|| (0..100u32).sum::<u32>());
+    }
+}
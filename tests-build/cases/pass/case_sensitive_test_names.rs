@@ -0,0 +1,23 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    // The generated statics must not collide, although the names only differ in case
+    #[test]
+    fn adc() {}
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn ADC() {}
+}
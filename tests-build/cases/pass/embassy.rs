@@ -62,3 +62,87 @@ mod tests2 {
         assert!(true)
     }
 }
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests3 {
+    struct Bus;
+    struct Sensor;
+
+    #[fixture]
+    async fn bus() -> Bus {
+        Bus
+    }
+
+    #[fixture]
+    fn sensor(bus: &mut Bus) -> Sensor {
+        let _ = bus;
+        Sensor
+    }
+
+    #[test]
+    fn takes_async_fixture(sensor: Sensor) {
+        let _ = sensor;
+    }
+}
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests4 {
+    struct Modem;
+
+    #[group_init]
+    async fn modem() -> Modem {
+        Modem
+    }
+
+    #[test]
+    async fn borrows_group_state(_modem: &mut Modem) {
+        assert!(true)
+    }
+
+    #[test]
+    fn borrows_group_state2(_modem: &Modem) {
+        assert!(true)
+    }
+}
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests5 {
+    use embassy_executor::Spawner;
+
+    #[embassy_executor::task]
+    async fn background() {}
+
+    #[init]
+    async fn init(spawner: Spawner) -> u32 {
+        spawner.spawn(background()).unwrap();
+        0
+    }
+
+    #[test]
+    async fn takes_spawner(_state: u32, spawner: Spawner) {
+        let _ = spawner;
+    }
+
+    #[test]
+    async fn takes_spawner_before_state(spawner: Spawner, _state: u32) {
+        let _ = spawner;
+    }
+
+    mod group {
+        use embassy_executor::Spawner;
+
+        #[group_init]
+        async fn runner(spawner: Spawner) -> u32 {
+            let _ = spawner;
+            0
+        }
+
+        #[test]
+        async fn takes_spawner_in_group(_state: &u32, spawner: Spawner) {
+            let _ = spawner;
+        }
+    }
+}
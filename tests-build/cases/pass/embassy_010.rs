@@ -0,0 +1,35 @@
+/*
+```cargo
+[dependencies]
+embassy-executor = { version = "0.10", features = ["executor-thread", "platform-riscv32"] }
+esp-hal = { version = "1.0.0", features = ["esp32c6"] } # for critical section implementation
+embedded-test = { path = "../../..", features = ["embassy-010"] }
+
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use embassy_executor::Spawner;
+    use esp_hal::*; // needs to be in scope, to prevent linker error about missing `critical_section` implementation
+
+    #[embassy_executor::task]
+    async fn background() {}
+
+    #[init]
+    async fn init(spawner: Spawner) -> u32 {
+        spawner.spawn(background().unwrap());
+        0
+    }
+
+    #[test]
+    async fn takes_spawner(_state: u32, spawner: Spawner) {
+        let _ = spawner;
+    }
+}
@@ -35,4 +35,29 @@ mod tests {
     async fn takes_no_state() {
         assert!(true)
     }
+
+    #[test(executor = esp_rtos::embassy::Executor::new())]
+    async fn takes_state_on_own_executor(_state: Context) {
+        assert!(true)
+    }
+
+    #[test]
+    async fn takes_spawner(_state: Context, spawner: embassy_executor::Spawner) {
+        let _ = spawner;
+    }
+}
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests2 {
+    // Only the async test needs an executor, if the module doesn't provide one
+    #[test(executor = esp_rtos::embassy::Executor::new())]
+    async fn own_executor() {
+        assert!(true)
+    }
+
+    #[test]
+    fn sync_test() {
+        assert!(true)
+    }
 }
@@ -0,0 +1,25 @@
+/*
+```cargo
+[dependencies]
+esp-hal = { version = "=1.0.0", features = ["esp32c6", "unstable"] }
+embedded-test = { path = "../../..", features = ["fault-handler"] }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[init]
+    fn init() -> esp_hal::peripherals::Peripherals {
+        esp_hal::init(esp_hal::Config::default())
+    }
+
+    // esp-hal defines `ExceptionHandler` itself, the fault handler must not clash with it
+    #[test]
+    fn it_works(_peripherals: esp_hal::peripherals::Peripherals) {}
+}
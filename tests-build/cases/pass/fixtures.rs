@@ -0,0 +1,78 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    struct State;
+    struct Dma(u32);
+    struct Uart(u32);
+    struct Spi(u32);
+
+    #[init]
+    fn init() -> State {
+        State
+    }
+
+    #[fixture]
+    fn dma() -> Dma {
+        Dma(1)
+    }
+
+    #[fixture]
+    fn uart(dma: &mut Dma) -> Uart {
+        dma.0 += 1;
+        Uart(dma.0)
+    }
+
+    #[fixture]
+    fn spi(dma: &Dma, uart: &Uart) -> Spi {
+        Spi(dma.0 + uart.0)
+    }
+
+    #[test]
+    fn takes_fixture(dma: Dma) {
+        assert_eq!(dma.0, 1)
+    }
+
+    #[test]
+    fn takes_dependent_fixtures(spi: Spi, uart: Uart, dma: Dma) {
+        assert_eq!((dma.0, uart.0, spi.0), (2, 2, 4))
+    }
+
+    #[test]
+    fn takes_state_and_fixture(_state: State, uart: Uart) {
+        assert_eq!(uart.0, 2)
+    }
+
+    #[test]
+    #[test_case(2)]
+    #[test_case(3)]
+    fn takes_fixture_and_case_argument(uart: Uart, expected: u32) {
+        assert!(uart.0 <= expected)
+    }
+
+    mod nested {
+        use super::{Dma, Uart};
+
+        // Overrides the fixture of the parent module, also for the fixtures depending on it
+        #[fixture]
+        fn dma() -> Dma {
+            Dma(10)
+        }
+
+        #[test]
+        fn takes_inherited_fixture(uart: Uart) {
+            assert_eq!(uart.0, 11)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+mod board {
+    pub struct Board;
+
+    pub fn init() -> Board {
+        Board
+    }
+}
+
+#[cfg(test)]
+mod uart {
+    use crate::board::Board;
+
+    #[embedded_test::test(init = crate::board::init, timeout = 500ms)]
+    #[tag("uart")]
+    fn loopback(_board: Board) {}
+
+    #[embedded_test::test(ignore = "needs rev-C board")]
+    fn ignored() {}
+
+    #[embedded_test::test(should_panic(expected = "overrun"))]
+    fn overrun() {
+        panic!("rx overrun")
+    }
+
+    #[embedded_test::test]
+    #[test_case(9600)]
+    #[test_case(115200)]
+    fn baudrate(baudrate: u32) {
+        assert!(baudrate > 0)
+    }
+}
+
+// Tests can be generated via macro_rules!
+#[cfg(test)]
+mod generated {
+    macro_rules! generate_test {
+        ($name:ident, $value:expr) => {
+            #[embedded_test::test]
+            fn $name() {
+                assert!($value > 0)
+            }
+        };
+    }
+
+    generate_test!(one, 1);
+    generate_test!(two, 2);
+}
@@ -0,0 +1,59 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    struct Modem {
+        commands: u32,
+    }
+
+    #[group_init]
+    fn modem() -> Modem {
+        Modem { commands: 0 }
+    }
+
+    #[test]
+    fn borrows_state_mutably(modem: &mut Modem) {
+        modem.commands += 1;
+    }
+
+    #[test]
+    #[test_case(1)]
+    #[test_case(2)]
+    fn borrows_state(modem: &Modem, commands: u32) {
+        assert!(modem.commands <= commands)
+    }
+
+    #[test]
+    fn takes_no_state() -> Result<(), ()> {
+        Ok(())
+    }
+
+    // Skipped by the group runner
+    #[test]
+    #[ignore = "modem firmware too old"]
+    fn ignored(_modem: &Modem) {}
+
+    // Nested modules are not part of the group
+    mod nested {
+        #[init]
+        fn init() -> u32 {
+            42
+        }
+
+        #[test]
+        fn takes_own_state(state: u32) {
+            assert_eq!(state, 42)
+        }
+    }
+}
@@ -0,0 +1,36 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[ignore = "needs rev-C board"]
+    fn ignored_with_reason() {
+        assert!(false)
+    }
+
+    #[test]
+    #[ignore_unless(target_pointer_width = "64")]
+    fn ignored_unless_predicate_holds() {
+        // Only compiled if the predicate holds, so this can use cfg'ed items
+        let _: u64 = usize::MAX as u64;
+    }
+
+    #[test]
+    #[ignore_unless(target_pointer_width = "32")]
+    #[test_case(1)]
+    #[test_case(2)]
+    fn not_ignored_if_predicate_holds(value: u32) {
+        assert!(value > 0)
+    }
+}
@@ -0,0 +1,41 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    struct Bench;
+
+    #[derive(Debug)]
+    enum BenchError {
+        NotConnected,
+    }
+
+    #[init]
+    fn init() -> Result<Bench, BenchError> {
+        Ok(Bench)
+    }
+
+    fn custom_init() -> Result<(), BenchError> {
+        Err(BenchError::NotConnected)
+    }
+
+    #[test]
+    fn takes_ok_state(_state: Bench) {
+        assert!(true)
+    }
+
+    #[test(init = custom_init)]
+    fn custom_init_without_state() {
+        assert!(true)
+    }
+}
@@ -0,0 +1,50 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    trait Codec: Sized + PartialEq + core::fmt::Debug {
+        fn decode(value: u32) -> Self;
+    }
+
+    impl Codec for u8 {
+        fn decode(value: u32) -> Self {
+            value as u8
+        }
+    }
+
+    impl Codec for u16 {
+        fn decode(value: u32) -> Self {
+            value as u16
+        }
+    }
+
+    // Generates the tests `roundtrip::<u8>` and `roundtrip::<u16>`
+    #[test]
+    #[instantiate(u8, u16)]
+    fn roundtrip<T: Codec>() {
+        assert_eq!(T::decode(1), T::decode(1))
+    }
+
+    // Generates the tests `decode::<u8>::case_1`, `decode::<u8>::zero`, `decode::<u16>::case_1`, ...
+    #[test]
+    #[instantiate(u8, u16)]
+    #[test_case(1)]
+    #[test_case(0; "zero")]
+    fn decode<T>(value: u32)
+    where
+        T: Codec,
+    {
+        assert_eq!(T::decode(value), T::decode(value))
+    }
+}
@@ -0,0 +1,54 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    pub struct Board;
+
+    #[init]
+    fn init() -> Board {
+        Board
+    }
+
+    #[test]
+    fn top_level(_board: Board) {}
+
+    // Tests in nested modules are named e.g. `tests::uart::loopback`
+    mod uart {
+        use super::Board;
+
+        // Inherits the init function of the parent module
+        #[test]
+        fn loopback(_board: Board) {}
+
+        mod dma {
+            use super::Board;
+
+            #[test]
+            fn loopback(_board: Board) {}
+        }
+    }
+
+    mod spi {
+        // Overrides the init function of the parent module
+        #[init]
+        fn init() -> u32 {
+            42
+        }
+
+        #[test]
+        fn loopback(value: u32) {
+            assert_eq!(value, 42)
+        }
+    }
+}
@@ -0,0 +1,52 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests(default_retry = 1)]
+mod tests {
+    #[init]
+    fn init() -> u32 {
+        42
+    }
+
+    #[test]
+    #[retry(3)]
+    fn retried(value: u32) {
+        assert_eq!(value, 42)
+    }
+
+    // Repeated by the runner, as the state is consumed by the test
+    #[test]
+    #[repeat(10)]
+    fn repeated_with_state(value: u32) {
+        assert_eq!(value, 42)
+    }
+}
+
+#[cfg(test)]
+#[embedded_test::tests(default_repeat = 2)]
+mod repeated_on_target {
+    #[test]
+    #[repeat(100)]
+    #[test_case(1)]
+    #[test_case(2)]
+    fn repeated_on_target(value: u32) -> Result<(), &'static str> {
+        if value > 0 {
+            Ok(())
+        } else {
+            Err("value must be positive")
+        }
+    }
+
+    #[test]
+    fn default_repeat() {}
+}
@@ -0,0 +1,27 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../..", features = ["fault-handler"] }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[should_fault]
+    fn faults() {
+        unsafe { core::arch::asm!("unimp") }
+    }
+
+    #[test]
+    #[should_fault(kind = "LoadFault")]
+    fn reads_unmapped_memory() {
+        let _ = unsafe { core::ptr::read_volatile(0xFFFF_FFF0 as *const u32) };
+    }
+}
@@ -0,0 +1,37 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn should_panic_with_message() {
+        let values: [u32; 0] = [];
+        let index = values.len();
+        let _ = values[index];
+    }
+
+    #[test]
+    #[should_panic = "boom"]
+    fn should_panic_with_message_short_form() {
+        panic!("boom")
+    }
+
+    #[test]
+    #[should_panic(expected = "boom")]
+    #[test_case(1)]
+    #[test_case(2)]
+    fn should_panic_with_message_for_all_cases(value: u32) {
+        panic!("boom {}", value)
+    }
+}
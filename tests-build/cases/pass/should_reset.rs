@@ -0,0 +1,38 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[init]
+    fn init() -> u32 {
+        0
+    }
+
+    #[test]
+    #[should_reset(within = 2s)]
+    fn watchdog_resets(_state: u32) {
+        loop {} // e.g. the watchdog is not fed
+    }
+
+    #[test]
+    #[should_reset]
+    #[timeout(5)]
+    fn software_reset() {}
+
+    // Fails: The panic clears the expected reset, so the test is not reported as reset when it's run again
+    #[test]
+    #[should_reset]
+    fn panics_instead_of_resetting(_state: u32) {
+        panic!("the reset was not triggered")
+    }
+}
@@ -0,0 +1,32 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    fn sensor_populated() -> bool {
+        false
+    }
+
+    #[test]
+    fn skips_at_runtime() {
+        if !sensor_populated() {
+            embedded_test::skip!("sensor not populated on this board revision");
+        }
+        assert!(false)
+    }
+
+    #[test]
+    fn skips_with_result() -> Result<(), &'static str> {
+        embedded_test::skip!("not applicable")
+    }
+}
@@ -0,0 +1,41 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use embedded_test::Retained;
+
+    #[unsafe(link_section = ".uninit.embedded_test")]
+    static PERSISTED: Retained<u32> = Retained::new();
+
+    #[init]
+    fn init() -> u32 {
+        embedded_test::stage()
+    }
+
+    #[test(stages = 2)]
+    fn persist_then_verify(stage: u32) {
+        match stage {
+            0 => PERSISTED.store(42),
+            _ => assert_eq!(PERSISTED.load(), Some(42)),
+        }
+    }
+
+    #[test(stages = 3)]
+    fn resets_itself() -> Result<(), ()> {
+        if embedded_test::stage() < 2 {
+            embedded_test::expect_reset();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+pub struct Board;
+pub type BoardAlias = Board;
+
+// The state and fixture types can be spelled differently, e.g. via an alias or the full path
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    #[init]
+    fn init() -> crate::Board {
+        crate::Board
+    }
+
+    #[teardown]
+    fn teardown(_state: super::BoardAlias, _outcome: embedded_test::Outcome) {}
+
+    #[fixture]
+    fn number() -> u32 {
+        7
+    }
+
+    #[fixture]
+    fn doubled(number: &core::primitive::u32) -> u64 {
+        u64::from(*number) * 2
+    }
+
+    #[test]
+    fn takes_alias(_state: &mut crate::BoardAlias, doubled: u64) {
+        assert_eq!(doubled, 14);
+    }
+
+    mod nested {
+        use crate::Board;
+
+        type Number = u32;
+
+        // Overrides the fixture `number` used by `doubled`
+        #[fixture]
+        fn number() -> Number {
+            21
+        }
+
+        #[test]
+        fn takes_full_path(_state: &Board, doubled: core::primitive::u64) {
+            assert_eq!(doubled, 42);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+pub trait Fixture {
+    fn value() -> u32;
+}
+
+pub struct W25Q32Fixture;
+
+impl Fixture for W25Q32Fixture {
+    fn value() -> u32 {
+        32
+    }
+}
+
+pub struct Mx25Fixture;
+
+impl Fixture for Mx25Fixture {
+    fn value() -> u32 {
+        25
+    }
+}
+
+#[cfg(test)]
+#[embedded_test::suite(F: crate::Fixture, default_timeout = 5)]
+mod flash_suite {
+    use crate::Fixture;
+
+    #[init]
+    fn init() -> u32 {
+        F::value()
+    }
+
+    #[test]
+    fn positive(value: u32) {
+        assert!(value > 0)
+    }
+
+    #[test]
+    #[test_case(1)]
+    fn larger_than(value: u32, limit: u32) {
+        assert!(value > limit)
+    }
+}
+
+// Generates the tests `tests::w25q32_fixture::positive`, `tests::mx25::positive`, ...
+#[cfg(test)]
+mod tests {
+    embedded_test::instantiate_suite!(flash_suite, crate::W25Q32Fixture);
+    embedded_test::instantiate_suite!(flash_suite, crate::Mx25Fixture as mx25);
+}
@@ -0,0 +1,24 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests(tags("hil"))]
+mod tests {
+    #[test]
+    #[tag("can", "loopback")]
+    #[tag("hil")]
+    #[meta(bench = "rev-c", owner = "firmware")]
+    fn tagged() {}
+
+    #[test]
+    fn inherits_module_tags() {}
+}
@@ -0,0 +1,101 @@
+/*
+```cargo
+[dependencies]
+embassy-executor = { version = "0.9", features = ["executor-thread", "arch-riscv32"] }
+esp-hal = { version = "1.0.0", features = ["esp32c6"] } # for critical section implementation
+embedded-test = { path = "../../..", features = ["embassy"] }
+
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests1 {
+    use embedded_test::Outcome;
+    use esp_hal::*; // needs to be in scope, to prevent linker error about missing `critical_section` implementation
+
+    struct Context;
+
+    #[init]
+    fn init() -> Context {
+        Context
+    }
+
+    #[teardown]
+    async fn teardown(_state: Context, outcome: Outcome) -> Result<(), &'static str> {
+        if outcome.is_success() {
+            Ok(())
+        } else {
+            Err("test failed")
+        }
+    }
+
+    #[test]
+    fn borrows_state(_state: &Context) {
+        assert!(true)
+    }
+
+    #[test]
+    async fn borrows_state_mut(_state: &mut Context) {
+        assert!(true)
+    }
+
+    #[test]
+    fn takes_no_state() -> Result<(), &'static str> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests2 {
+    struct Context;
+
+    #[init]
+    fn init() -> Context {
+        Context
+    }
+
+    #[teardown]
+    fn teardown() {}
+
+    #[test]
+    fn takes_state(_state: Context) {
+        assert!(true)
+    }
+}
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests3 {
+    use embedded_test::Outcome;
+
+    struct Board;
+
+    #[init]
+    fn init() -> Board {
+        Board
+    }
+
+    // Takes the state only, the outcome is recognized by its type
+    #[teardown]
+    fn teardown(_board: Board) {}
+
+    #[test]
+    fn borrows_state(_board: &Board) {}
+
+    mod outcome_only {
+        #[teardown]
+        fn teardown(outcome: super::Outcome) {
+            assert!(outcome.is_success())
+        }
+
+        #[test]
+        fn takes_no_state() {}
+    }
+}
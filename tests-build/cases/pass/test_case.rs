@@ -0,0 +1,49 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    pub enum Parity {
+        None,
+        Even,
+    }
+
+    struct Context;
+
+    #[init]
+    fn init() -> Context {
+        Context
+    }
+
+    #[test]
+    #[test_case(115200, Parity::None)]
+    #[test_case(9600, Parity::Even; "slow_even")]
+    fn takes_state_and_args(_state: Context, _baud: u32, _parity: Parity) {
+        assert!(true)
+    }
+
+    #[test]
+    #[test_case(1)]
+    #[test_case(2)]
+    fn takes_args(value: u32) {
+        assert!(value > 0)
+    }
+
+    #[test]
+    #[should_panic]
+    #[test_case("abc")]
+    #[test_case("def")]
+    fn should_panic_for_all_cases(_value: &str) {
+        assert!(false)
+    }
+}
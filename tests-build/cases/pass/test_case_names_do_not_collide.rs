@@ -0,0 +1,26 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    // The generated statics of a case must not collide with those of a test named like the case
+    #[test]
+    #[test_case(1)]
+    #[test_case(2)]
+    fn foo(value: u32) {
+        assert!(value > 0)
+    }
+
+    #[test]
+    fn foo_case_1() {}
+}
@@ -0,0 +1,46 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests(default_timeout = 250ms, tags("hil"))]
+mod tests {
+    use embedded_test::TestInfo;
+
+    #[init]
+    fn init(info: &TestInfo) -> usize {
+        info.name.len()
+    }
+
+    #[teardown]
+    fn teardown(outcome: embedded_test::Outcome, info: &embedded_test::TestInfo) {
+        let _ = (outcome, info.name);
+    }
+
+    #[test]
+    #[tag("slow")]
+    fn takes_info(state: usize, info: &TestInfo) {
+        assert_eq!(state, info.name.len());
+        assert_eq!(info.tags, &["hil", "slow"]);
+    }
+
+    #[test]
+    #[test_case(1)]
+    fn takes_info_before_case_argument(info: &TestInfo, _state: usize, n: u32) {
+        assert_eq!(info.name, "takes_info_before_case_argument::case_1");
+        assert_eq!(n, 1);
+    }
+
+    #[test]
+    fn current_test() {
+        assert!(embedded_test::current_test().is_some_and(|info| !info.should_panic));
+    }
+}
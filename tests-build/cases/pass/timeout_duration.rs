@@ -0,0 +1,41 @@
+/*
+```cargo
+[dependencies]
+embedded-test = { path = "../../.." }
+[lib]
+harness = false
+```
+ */
+
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests(default_timeout = 500ms)]
+mod tests {
+    #[test]
+    fn default_timeout() {}
+
+    #[test]
+    #[timeout(250ms)]
+    fn millis_literal() {}
+
+    #[test]
+    #[timeout("2min")]
+    fn string_literal() {}
+
+    #[test]
+    #[timeout(millis = 150)]
+    fn millis_field() {}
+
+    #[test]
+    #[timeout(secs = 3)]
+    fn secs_field() {}
+}
+
+#[cfg(test)]
+#[embedded_test::tests(default_timeout(millis = 200))]
+mod default_timeout_millis {
+    #[test]
+    fn default_timeout() {}
+}